@@ -0,0 +1,53 @@
+#!/usr/bin/env python3
+"""Prototype of 2022 day 11, for cross-checking the rust solvers.
+
+Usage: day11.py PART [INPUT]. The input is read from stdin without a path.
+"""
+import math
+import re
+import sys
+
+
+def parse(text):
+    monkeys = []
+    for block in text.strip().split("\n\n"):
+        lines = block.splitlines()
+        monkeys.append({
+            "items": [int(n) for n in re.findall(r"\d+", lines[1])],
+            "operation": lines[2].split("=")[1].strip(),
+            "test": int(lines[3].split()[-1]),
+            "on_true": int(lines[4].split()[-1]),
+            "on_false": int(lines[5].split()[-1]),
+            "inspections": 0,
+        })
+    return monkeys
+
+
+def solve(monkeys, rounds, relieve):
+    for _ in range(rounds):
+        for monkey in monkeys:
+            for old in monkey["items"]:
+                worry = relieve(eval(monkey["operation"], {}, {"old": old}))
+                target = monkey["on_true"] if worry % monkey["test"] == 0 else monkey["on_false"]
+                monkeys[target]["items"].append(worry)
+            monkey["inspections"] += len(monkey["items"])
+            monkey["items"] = []
+    top = sorted(m["inspections"] for m in monkeys)
+    return top[-1] * top[-2]
+
+
+def main():
+    part = int(sys.argv[1])
+    text = open(sys.argv[2]).read() if len(sys.argv) > 2 else sys.stdin.read()
+    monkeys = parse(text)
+    print(f"{len(monkeys)} monkeys", file=sys.stderr)
+
+    if part == 1:
+        print(solve(monkeys, 20, lambda w: w // 3))
+    else:
+        modulus = math.prod(m["test"] for m in monkeys)
+        print(solve(monkeys, 10000, lambda w: w % modulus))
+
+
+if __name__ == "__main__":
+    main()
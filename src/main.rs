@@ -1,7 +1,11 @@
 mod rookrustpad;
 
+use std::env;
+use std::process;
+
 use actix_web::{App, HttpServer, middleware};
 use rookrustpad::app_configuration::AppConfiguration;
+use rookrustpad::app_settings::AppSettings;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -10,9 +14,21 @@ async fn main() -> std::io::Result<()> {
 
     env_logger::init();
 
-    println!("Starting server on {}:{}", listen_addr, listen_port);
+    let app_settings = AppSettings::load()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
 
-    let app_config = AppConfiguration::create_default();
+    // Any arguments are a command to run instead of the server.
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(error) = rookrustpad::cli::run(&args, &app_settings) {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    println!("Starting server on {}:{}", listen_addr, listen_port);
+    let app_config = AppConfiguration::create(&app_settings);
 
     HttpServer::new(move || {
         App::new()
@@ -1,4 +1,6 @@
 pub mod api;
 pub mod service;
 pub mod page;
-pub mod app_configuration;
\ No newline at end of file
+pub mod app_configuration;
+pub mod app_settings;
+pub mod cli;
\ No newline at end of file
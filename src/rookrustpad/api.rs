@@ -1,3 +1,5 @@
 pub mod test_handlers;
 pub mod static_handler;
-pub mod aoc_handler;
\ No newline at end of file
+pub mod aoc_handler;
+pub mod mock_aoc_handler;
+pub mod response_format;
\ No newline at end of file
@@ -1,16 +1,107 @@
+use std::future::Future;
 use std::sync::Arc;
 
-use actix_web::{get, web, Responder};
+use actix_web::http::{header, StatusCode};
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, ResponseError};
+use futures::stream::{self, StreamExt, TryStreamExt};
 
-use crate::rookrustpad::service::aoc::AocService;
+use crate::rookrustpad::page::aoc::{moved_answer_path, redirect};
+use crate::rookrustpad::service::aoc::ocr::AocOcrError;
+use crate::rookrustpad::service::aoc::output::AocOutput;
+use crate::rookrustpad::app_settings::TeamSettings;
+use crate::rookrustpad::service::aoc::params::AocParamInput;
+use crate::rookrustpad::service::aoc::{AocAnswer, AocAnswerRequest, AocProblem, AocService};
+use crate::rookrustpad::service::solver_pool::{SolverPool, SolverPoolError};
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq)]
-struct AocAnswerRequest {
+/// Solver parameters from the query string, leaving out the names the resource uses itself.
+pub fn query_params(req: &HttpRequest, reserved: &[&str]) -> Result<AocParamInput, actix_web::Error> {
+    let mut params = web::Query::<AocParamInput>::from_query(req.query_string())?.into_inner();
+    params.retain(|name, _| !reserved.contains(&name.as_str()));
+    Ok(params)
+}
+
+/// Whose inputs a request is for: ?user= when given, otherwise the identity
+/// an authenticating proxy put in the configured header.
+pub fn request_user(req: &HttpRequest, user: Option<String>) -> Option<String> {
+    user.filter(|user| !user.is_empty()).or_else(|| {
+        let team = req.app_data::<web::Data<TeamSettings>>()?;
+        let value = req.headers().get(team.identity_header.as_deref()?)?;
+        value.to_str().ok().map(|user| user.trim().to_string()).filter(|user| !user.is_empty())
+    })
+}
+
+impl ResponseError for SolverPoolError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SolverPoolError::QueueFull { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            SolverPoolError::Canceled => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let SolverPoolError::QueueFull { retry_after } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+        response.body(self.to_string())
+    }
+}
+
+impl ResponseError for AocOcrError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+/// Runs a solver on the solver pool so it does not tie up an http worker.
+pub async fn run_answer(
+    aoc_service: &Arc<AocService>,
+    solver_pool: &SolverPool,
+    request: AocAnswerRequest) -> Result<AocAnswer, SolverPoolError>
+{
+    let aoc_service = aoc_service.clone();
+    solver_pool.run(move || aoc_service.get_answer(&request)).await
+}
+
+/// Like run_answer without recording the run or stopping timers, for pages that only show answers.
+pub async fn preview_answer(
+    aoc_service: &Arc<AocService>,
+    solver_pool: &SolverPool,
+    request: AocAnswerRequest) -> Result<AocAnswer, SolverPoolError>
+{
+    let aoc_service = aoc_service.clone();
+    solver_pool.run(move || aoc_service.compute_answer(&request)).await
+}
+
+/// Runs every user's input for a day, see AocService::team_requests.
+pub async fn run_team_answers(
+    aoc_service: &Arc<AocService>,
+    solver_pool: &Arc<SolverPool>,
     year: u32,
-    day: u32,
-    part: u8,
+    day: u32) -> Result<Vec<AocAnswer>, actix_web::Error>
+{
+    let requests = aoc_service.team_requests(year, day)?;
+    let answers = stream::iter(answer_futures(aoc_service, solver_pool, requests))
+        .buffered(solver_pool.workers())
+        .try_collect()
+        .await?;
+    Ok(answers)
+}
+
+const BENCHMARK_QUERY: &[&str] = &["user", "dataset", "variant", "iterations"];
+
+/// Any other query parameters are passed to the solver.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct AocBenchmarkQuery {
+    user: Option<String>,
     dataset: Option<String>,
+    variant: Option<String>,
+    iterations: Option<u32>,
 }
 
 #[get("problems")]
@@ -18,18 +109,194 @@ async fn get_problems(aoc_service: web::Data<Arc<AocService>>) -> impl Responder
     web::Json(aoc_service.list_problems())
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct AocUserQuery {
+    user: Option<String>,
+}
+
 #[get("inputs")]
-async fn get_inputs(aoc_service: web::Data<Arc<AocService>>) -> impl Responder {
-    aoc_service.list_inputs().map(|inputs| web::Json(inputs))
+async fn get_inputs(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    query: web::Query<AocUserQuery>) -> impl Responder
+{
+    let user = request_user(&req, query.into_inner().user);
+    aoc_service.list_user_inputs(&user).map(web::Json)
+}
+
+#[get("users")]
+async fn get_users(aoc_service: web::Data<Arc<AocService>>) -> impl Responder {
+    aoc_service.list_users().map(web::Json)
+}
+
+/// Every user's answers for a day, on their own inputs.
+#[get("team/{year}/{day}")]
+async fn get_team(
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    path: web::Path<AocDayPath>) -> Result<impl Responder, actix_web::Error>
+{
+    Ok(web::Json(run_team_answers(&aoc_service, &solver_pool, path.year, path.day).await?))
+}
+
+#[get("coverage")]
+async fn get_coverage(aoc_service: web::Data<Arc<AocService>>) -> impl Responder {
+    aoc_service.get_coverage().map(web::Json)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct AocDayPath {
+    year: u32,
+    day: u32,
 }
 
+#[get("{year}/{day}/source")]
+async fn get_source(aoc_service: web::Data<Arc<AocService>>, path: web::Path<AocDayPath>) -> impl Responder {
+    web::Json(aoc_service.get_day_source(path.year, path.day))
+}
+
+/// Example inputs and answers found in a day's saved statement.
+#[get("examples/{year}/{day}")]
+async fn get_examples(aoc_service: web::Data<Arc<AocService>>, path: web::Path<AocDayPath>) -> impl Responder {
+    aoc_service.extract_examples(path.year, path.day).map(web::Json)
+}
+
+#[get("pool")]
+async fn get_pool(solver_pool: web::Data<Arc<SolverPool>>) -> impl Responder {
+    web::Json(solver_pool.stats())
+}
+
+/// Answers are served by the negotiated /aoc answer resource.
 #[get("answer/{year}/{day}/{part}")]
-async fn get_answer(
+async fn get_answer(req: HttpRequest, request: web::Path<AocAnswerRequest>) -> impl Responder {
+    redirect(moved_answer_path(&req, &request, Some("json")))
+}
+
+#[get("benchmark/{year}/{day}/{part}")]
+async fn get_benchmark(
+    req: HttpRequest,
     aoc_service: web::Data<Arc<AocService>>,
-    request: web::Path<AocAnswerRequest>) -> impl Responder
+    solver_pool: web::Data<Arc<SolverPool>>,
+    problem: web::Path<AocProblem>,
+    query: web::Query<AocBenchmarkQuery>) -> Result<impl Responder, actix_web::Error>
 {
-    let answer = aoc_service.get_answer(request.year, request.day, request.part, &request.dataset);
-    web::Json(answer)
+    // All iterations run as one job so a benchmark holds a single solver thread.
+    let aoc_service = aoc_service.get_ref().clone();
+    let AocProblem { year, day, part } = problem.into_inner();
+    let AocBenchmarkQuery { user, dataset, variant, iterations } = query.into_inner();
+    let params = query_params(&req, BENCHMARK_QUERY)?;
+    let request = AocAnswerRequest { year, day, part, user: request_user(&req, user), dataset, variant, params };
+
+    let benchmark = solver_pool
+        .run(move || aoc_service.benchmark(&request, iterations.unwrap_or(10)))
+        .await?;
+
+    Ok(web::Json(benchmark))
+}
+
+#[get("variants/{year}/{day}/{part}")]
+async fn get_variants(aoc_service: web::Data<Arc<AocService>>, problem: web::Path<AocProblem>) -> impl Responder {
+    web::Json(aoc_service.list_variants(problem.year, problem.day, problem.part))
+}
+
+/// Benchmarks every variant of a problem on the same input and checks they agree.
+#[get("compare/{year}/{day}/{part}")]
+async fn get_comparison(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    problem: web::Path<AocProblem>,
+    query: web::Query<AocBenchmarkQuery>) -> Result<impl Responder, actix_web::Error>
+{
+    // Like a benchmark, the whole comparison is one job so the variants get the same conditions.
+    let aoc_service = aoc_service.get_ref().clone();
+    let AocProblem { year, day, part } = problem.into_inner();
+    let AocBenchmarkQuery { user, dataset, iterations, .. } = query.into_inner();
+    let params = query_params(&req, BENCHMARK_QUERY)?;
+    let request = AocAnswerRequest { year, day, part, user: request_user(&req, user), dataset, variant: None, params };
+
+    let comparison = solver_pool
+        .run(move || aoc_service.compare(&request, iterations.unwrap_or(10)))
+        .await?;
+
+    Ok(web::Json(comparison))
+}
+
+/// Requests without a user are for the caller's identity, when there is one.
+fn with_request_user(req: &HttpRequest, requests: Vec<AocAnswerRequest>) -> Vec<AocAnswerRequest> {
+    requests
+        .into_iter()
+        .map(|request| AocAnswerRequest { user: request_user(req, request.user.clone()), ..request })
+        .collect()
+}
+
+#[post("answers")]
+async fn post_answers(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    requests: web::Json<Vec<AocAnswerRequest>>) -> Result<impl Responder, SolverPoolError>
+{
+    // Answers come back in the same order they were requested.
+    let parallelism = solver_pool.workers();
+    let requests = with_request_user(&req, requests.into_inner());
+    let answers: Vec<AocAnswer> = stream::iter(answer_futures(&aoc_service, &solver_pool, requests))
+        .buffered(parallelism)
+        .try_collect()
+        .await?;
+
+    Ok(web::Json(answers))
+}
+
+#[post("answers/stream")]
+async fn stream_answers(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    requests: web::Json<Vec<AocAnswerRequest>>) -> impl Responder
+{
+    // Each answer is written as a line of JSON as soon as it finishes. The
+    // status is already sent, so pool errors become failed answers.
+    let parallelism = solver_pool.workers();
+    let requests = with_request_user(&req, requests.into_inner());
+    let lines = stream::iter(answer_futures(&aoc_service, &solver_pool, requests.clone()))
+        .enumerate()
+        .map(|(n, answer)| async move { (n, answer.await) })
+        .buffer_unordered(parallelism)
+        .map(move |(n, answer)| {
+            let answer = answer.unwrap_or_else(|error| AocAnswer::failed(&requests[n], error.to_string()));
+            serde_json::to_vec(&answer).map(|mut line| {
+                line.push(b'\n');
+                web::Bytes::from(line)
+            })
+        });
+
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(lines)
+}
+
+/// Reads the block letters in a posted drawing.
+#[post("ocr")]
+async fn read_drawing(drawing: String) -> Result<web::Json<AocOutput>, AocOcrError> {
+    Ok(web::Json(AocOutput::from_letters(&drawing)?))
+}
+
+fn answer_futures(
+    aoc_service: &Arc<AocService>,
+    solver_pool: &Arc<SolverPool>,
+    requests: Vec<AocAnswerRequest>) -> impl Iterator<Item = impl Future<Output = Result<AocAnswer, SolverPoolError>>>
+{
+    let aoc_service = aoc_service.clone();
+    let solver_pool = solver_pool.clone();
+
+    requests.into_iter().map(move |request| {
+        let aoc_service = aoc_service.clone();
+        let solver_pool = solver_pool.clone();
+        async move {
+            run_answer(&aoc_service, &solver_pool, request).await
+        }
+    })
 }
 
 pub fn create_aoc_handlers(cfg: &mut web::ServiceConfig) {
@@ -38,6 +305,117 @@ pub fn create_aoc_handlers(cfg: &mut web::ServiceConfig) {
         web::scope("/api/aoc")
             .service(get_problems)
             .service(get_inputs)
+            .service(get_users)
+            .service(get_team)
+            .service(get_coverage)
+            .service(get_pool)
             .service(get_answer)
+            .service(get_benchmark)
+            .service(get_variants)
+            .service(get_comparison)
+            .service(get_source)
+            .service(get_examples)
+            .service(post_answers)
+            .service(stream_answers)
+            .service(read_drawing)
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::history::AocRunHistory;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+    use crate::rookrustpad::service::aoc::timer::AocTimers;
+
+    fn aoc_service(directory: &tempfile::TempDir) -> Arc<AocService> {
+        let path = |name: &str| directory.path().join(name).to_string_lossy().into_owned();
+        let source = DirectoryInputSource::new("www/aoc_input");
+        let mut aoc_service = AocService::create_default(AocInputSources::new(vec![Box::new(source)]), String::from("www/aoc_problems"));
+        aoc_service.timers = AocTimers::new(&path("timers.json"));
+        aoc_service.history = AocRunHistory::new(&path("history"));
+        Arc::new(aoc_service)
+    }
+
+    macro_rules! app {
+        ($directory:expr, $solver_pool:expr) => {
+            test::init_service(App::new()
+                .app_data(web::Data::new(aoc_service($directory)))
+                .app_data(web::Data::new($solver_pool))
+                .service(web::scope("/api/aoc").service(post_answers).service(stream_answers))).await
+        };
+    }
+
+    fn batch(uri: &str) -> test::TestRequest {
+        test::TestRequest::post()
+            .uri(uri)
+            .set_json(json!([
+                { "year": 2022, "day": 11, "part": 2, "dataset": "test", "variant": null },
+                { "year": 2022, "day": 30, "part": 1, "dataset": null, "variant": null },
+            ]))
+    }
+
+    #[actix_web::test]
+    async fn answers_a_mixed_batch_in_order() {
+        let directory = tempfile::tempdir().unwrap();
+        let app = app!(&directory, Arc::new(SolverPool::new(2, 4, 5)));
+
+        let answers: Vec<Value> = test::call_and_read_body_json(&app, batch("/api/aoc/answers").to_request()).await;
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[0]["result"], json!({ "Ok": "2713310158" }));
+        assert_eq!(answers[0]["expected"], json!("2713310158"));
+        // A problem nobody solved is an answer with an error, not a failed batch.
+        assert_eq!((answers[1]["day"].clone(), answers[1]["part"].clone()), (json!(30), json!(1)));
+        assert_eq!(answers[1]["result"], json!({ "Err": "Problem not found." }));
+        assert_eq!(answers[1]["variant"], Value::Null);
+    }
+
+    #[actix_web::test]
+    async fn streams_a_mixed_batch_as_lines() {
+        let directory = tempfile::tempdir().unwrap();
+        let app = app!(&directory, Arc::new(SolverPool::new(2, 4, 5)));
+
+        let response = test::call_service(&app, batch("/api/aoc/answers/stream").to_request()).await;
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "application/x-ndjson");
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        let mut answers: Vec<Value> = body.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        answers.sort_by_key(|answer| answer["day"].as_u64());
+
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[0]["result"], json!({ "Ok": "2713310158" }));
+        assert_eq!(answers[1]["result"], json!({ "Err": "Problem not found." }));
+    }
+
+    #[actix_web::test]
+    async fn a_full_pool_turns_the_batch_away_and_fails_streamed_answers() {
+        let directory = tempfile::tempdir().unwrap();
+        let solver_pool = Arc::new(SolverPool::new(1, 0, 7));
+        let app = app!(&directory, solver_pool.clone());
+        let (release, released) = channel::<()>();
+        let blocking = solver_pool.submit(move || released.recv().unwrap()).unwrap();
+
+        let response = test::call_service(&app, batch("/api/aoc/answers").to_request()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "7");
+        assert_eq!(test::read_body(response).await, "Solver queue is full, retry in 7 seconds.");
+
+        // The stream's status is sent before the answers run, so each one fails on its own.
+        let response = test::call_service(&app, batch("/api/aoc/answers/stream").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        let answers: Vec<Value> = body.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(answers.len(), 2);
+        for answer in answers {
+            assert_eq!(answer["result"], json!({ "Err": "Solver queue is full, retry in 7 seconds." }));
+        }
+
+        release.send(()).unwrap();
+        blocking.await.unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::thread;
+
+use actix_web::http::StatusCode;
+use actix_web::{get, middleware, post, web, App, HttpRequest, HttpResponse, HttpServer};
+use serde::Deserialize;
+
+use crate::rookrustpad::service::aoc::mock::{AocMock, AocMockResponse, AocMockSettings};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocMockAnswerForm {
+    level: u8,
+    answer: String,
+}
+
+fn session(req: &HttpRequest) -> Option<String> {
+    req.cookie("session").map(|cookie| cookie.value().to_string())
+}
+
+fn respond(response: AocMockResponse) -> HttpResponse {
+    HttpResponse::build(StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+        .content_type(response.content_type)
+        .body(response.body)
+}
+
+#[get("/{year}/day/{day}/input")]
+async fn mock_input(req: HttpRequest, mock: web::Data<Arc<AocMock>>, path: web::Path<(u32, u32)>) -> HttpResponse {
+    let (year, day) = path.into_inner();
+    respond(mock.input(session(&req).as_deref(), year, day))
+}
+
+#[get("/{year}/day/{day}")]
+async fn mock_puzzle(req: HttpRequest, mock: web::Data<Arc<AocMock>>, path: web::Path<(u32, u32)>) -> HttpResponse {
+    let (year, day) = path.into_inner();
+    respond(mock.puzzle(session(&req).as_deref(), year, day))
+}
+
+#[post("/{year}/day/{day}/answer")]
+async fn mock_answer(
+    req: HttpRequest,
+    mock: web::Data<Arc<AocMock>>,
+    path: web::Path<(u32, u32)>,
+    form: web::Form<AocMockAnswerForm>) -> HttpResponse
+{
+    let (year, day) = path.into_inner();
+    respond(mock.answer(session(&req).as_deref(), year, day, form.level, &form.answer))
+}
+
+#[get("/{year}/leaderboard/private/view/{file}")]
+async fn mock_leaderboard(req: HttpRequest, mock: web::Data<Arc<AocMock>>, path: web::Path<(u32, String)>) -> HttpResponse {
+    let (year, file) = path.into_inner();
+    match file.strip_suffix(".json") {
+        Some(id) => respond(mock.leaderboard(session(&req).as_deref(), year, id)),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+pub fn create_mock_aoc_handlers(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(mock_input)
+        .service(mock_puzzle)
+        .service(mock_answer)
+        .service(mock_leaderboard);
+}
+
+/// Serves the mock until it is stopped. It gets a thread and runtime of its own
+/// since commands are run from inside the main runtime.
+pub fn run_mock_aoc(settings: &AocMockSettings) -> Result<(), Box<dyn Error>> {
+    let mock = Arc::new(AocMock::new(settings.clone())?);
+    let port = settings.port;
+    println!("Mock Advent of Code on 127.0.0.1:{} with fixtures from {}, the time there is {}",
+        port, settings.fixtures, mock.now().to_rfc3339());
+
+    let server = thread::spawn(move || {
+        actix_web::rt::System::new().block_on(async move {
+            HttpServer::new(move || {
+                App::new()
+                    .wrap(middleware::Logger::default())
+                    .app_data(web::Data::new(mock.clone()))
+                    .configure(create_mock_aoc_handlers)
+            })
+            .workers(1)
+            .bind(("127.0.0.1", port))?
+            .run()
+            .await
+        })
+    });
+
+    server.join().map_err(|_| "The mock server stopped unexpectedly")??;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use actix_web::dev::ServerHandle;
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::client::{AocClient, AocClientSettings};
+    use crate::rookrustpad::service::aoc::history::AocRunHistory;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+    use crate::rookrustpad::service::aoc::submission::{parse_response, AocSubmissionLog, AocSubmissionVerdict};
+    use crate::rookrustpad::service::aoc::timer::AocTimers;
+    use crate::rookrustpad::service::aoc::AocService;
+
+    fn mock_settings() -> AocMockSettings {
+        AocMockSettings {
+            port: 0,
+            fixtures: String::from("fixtures/aoc"),
+            now: Some(String::from("2022-12-13T04:59:00Z")),
+            ..AocMockSettings::default()
+        }
+    }
+
+    /// Serves the mock on a free port until the handle is stopped.
+    async fn start_mock(settings: AocMockSettings) -> (u16, ServerHandle) {
+        let mock = Arc::new(AocMock::new(settings).unwrap());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(mock.clone()))
+                .configure(create_mock_aoc_handlers)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        (port, handle)
+    }
+
+    /// A service talking to the mock, keeping everything it writes in directory.
+    fn service(port: u16, directory: &Path) -> Arc<AocService> {
+        let path = |name: &str| directory.join(name).to_string_lossy().into_owned();
+        let source = DirectoryInputSource::new(&path("input"));
+        let mut aoc_service = AocService::new(AocInputSources::new(vec![Box::new(source)]), path("problems"));
+        aoc_service.client = AocClient::new(AocClientSettings {
+            base_url: format!("http://127.0.0.1:{}", port),
+            session: Some(String::from("test-session")),
+            min_interval: 0.0,
+            timeout: 5,
+            submission_log: path("submissions.jsonl"),
+            leaderboard_id: Some(String::from("1501234")),
+            leaderboard_directory: path("leaderboards"),
+            ..AocClientSettings::default()
+        });
+        aoc_service.submissions = AocSubmissionLog::new(&path("submissions.jsonl"));
+        aoc_service.timers = AocTimers::new(&path("timers.json"));
+        aoc_service.history = AocRunHistory::new(&path("history"));
+        Arc::new(aoc_service)
+    }
+
+    #[actix_web::test]
+    async fn downloads_an_input_once() {
+        // A second request in the minute would get a 429.
+        let (port, handle) = start_mock(AocMockSettings { requests_per_minute: 1, ..mock_settings() }).await;
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(port, directory.path());
+
+        let downloads = web::block(move || {
+            let first = aoc_service.download_input(2022, 11).unwrap();
+            let second = aoc_service.download_input(2022, 11).unwrap();
+            let locked = aoc_service.download_input(2022, 14).unwrap_err().to_string();
+            (first, second, locked)
+        }).await.unwrap();
+        handle.stop(true).await;
+
+        let (first, second, locked) = downloads;
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(second.locations, first.locations);
+        assert_eq!(
+            fs::read_to_string(directory.path().join("input/2022/day_11_2.txt")).unwrap(),
+            fs::read_to_string("fixtures/aoc/2022/day_11.input.txt").unwrap());
+        assert!(locked.ends_with("returned 429"), "{}", locked);
+    }
+
+    #[actix_web::test]
+    async fn refuses_inputs_before_they_unlock() {
+        let (port, handle) = start_mock(mock_settings()).await;
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(port, directory.path());
+
+        let error = web::block(move || aoc_service.download_input(2022, 14).unwrap_err().to_string()).await.unwrap();
+        handle.stop(true).await;
+
+        assert!(error.ends_with("is not available, the puzzle may not be unlocked yet"), "{}", error);
+    }
+
+    #[actix_web::test]
+    async fn submits_answers_and_keeps_to_the_cooldown() {
+        let (port, handle) = start_mock(AocMockSettings { wrong_answer_wait: 90, ..mock_settings() }).await;
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(port, directory.path());
+
+        let results = web::block(move || {
+            let too_low = aoc_service.submit_answer(2022, 11, 1, "100").unwrap();
+            let held_back = aoc_service.submit_answer(2022, 11, 1, "200").unwrap_err().to_string();
+            // Sent anyway, the mock still counts the cooldown.
+            let (verdict, wait, _) = parse_response(&aoc_service.client
+                .post_form("/2022/day/11/answer", &[("level", "1"), ("answer", "200")])
+                .unwrap());
+            (too_low, held_back, verdict, wait, aoc_service.list_submissions(2022, 11, 1).unwrap().len())
+        }).await.unwrap();
+        handle.stop(true).await;
+
+        let (too_low, held_back, verdict, wait, logged) = results;
+        assert_eq!(too_low.verdict, AocSubmissionVerdict::TooLow);
+        assert_eq!(too_low.wait, Some(90));
+        assert!(too_low.message.contains("You have 1m 30s left to wait."), "{}", too_low.message);
+        assert!(held_back.starts_with("AoC asked to wait, try again in "), "{}", held_back);
+        assert_eq!(verdict, AocSubmissionVerdict::Wait);
+        assert!(wait.is_some_and(|wait| wait > 0 && wait <= 90), "{:?}", wait);
+        assert_eq!(logged, 1);
+    }
+
+    #[actix_web::test]
+    async fn saves_a_correct_answer() {
+        let (port, handle) = start_mock(mock_settings()).await;
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(port, directory.path());
+
+        let results = web::block(move || {
+            // The expected answer goes in the main input's sidecar.
+            aoc_service.download_input(2022, 11).unwrap();
+            let correct = aoc_service.submit_answer(2022, 11, 1, "66124").unwrap();
+            let again = aoc_service.submit_answer(2022, 11, 1, "66124").unwrap_err().to_string();
+            let expected = aoc_service.load_input_metadata("2022/day_11_1.txt").unwrap().and_then(|m| m.expected_answer(1).cloned());
+            let part_two = aoc_service.submit_answer(2022, 11, 2, "19309892877").unwrap();
+            (correct, again, expected, part_two)
+        }).await.unwrap();
+        handle.stop(true).await;
+
+        let (correct, again, expected, part_two) = results;
+        assert_eq!((correct.verdict, correct.wait), (AocSubmissionVerdict::Correct, None));
+        assert_eq!(again, "The part was already solved with 66124");
+        assert_eq!(expected, Some(String::from("66124")));
+        assert_eq!(part_two.verdict, AocSubmissionVerdict::Correct);
+    }
+
+    #[actix_web::test]
+    async fn fetches_the_leaderboard_once_in_a_while() {
+        let (port, handle) = start_mock(mock_settings()).await;
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(port, directory.path());
+
+        let results = web::block(move || {
+            let fetched = aoc_service.fetch_leaderboard(2022).unwrap();
+            let saved = aoc_service.load_leaderboard(2022).unwrap();
+            let again = aoc_service.fetch_leaderboard(2022).unwrap_err().to_string();
+            (fetched, saved, again)
+        }).await.unwrap();
+        handle.stop(true).await;
+
+        let (fetched, saved, again) = results;
+        assert_eq!(fetched.event, "2022");
+        assert_eq!(fetched.members.len(), 4);
+        assert_eq!(saved.map(|saved| saved.members.len()), Some(4));
+        assert!(again.starts_with("The leaderboard was fetched 0 minutes ago"), "{}", again);
+    }
+}
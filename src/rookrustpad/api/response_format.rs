@@ -0,0 +1,50 @@
+use actix_web::error::{ErrorBadRequest, ErrorNotAcceptable};
+use actix_web::http::header::{self, Header};
+use actix_web::{HttpRequest, Result as AwResult};
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum ResponseFormat {
+    Html,
+    Json,
+    Text,
+}
+
+impl ResponseFormat {
+    pub fn from_name(name: &str) -> Option<ResponseFormat> {
+        match name {
+            "html" => Some(ResponseFormat::Html),
+            "json" => Some(ResponseFormat::Json),
+            "text" | "txt" => Some(ResponseFormat::Text),
+            _ => None,
+        }
+    }
+
+    fn from_mime(mime: &str) -> Option<ResponseFormat> {
+        match mime {
+            "text/html" | "*/*" | "text/*" => Some(ResponseFormat::Html),
+            "application/json" | "application/*" => Some(ResponseFormat::Json),
+            "text/plain" => Some(ResponseFormat::Text),
+            _ => None,
+        }
+    }
+
+    /// Picks a format from an explicit ?format= value, otherwise from the
+    /// Accept header in order of preference. No Accept header means html.
+    pub fn negotiate(req: &HttpRequest, format: Option<&str>) -> AwResult<ResponseFormat> {
+        if let Some(format) = format {
+            return ResponseFormat::from_name(format)
+                .ok_or_else(|| ErrorBadRequest(format!("Unknown format: {}", format)));
+        }
+
+        let accept = match header::Accept::parse(req) {
+            Ok(accept) if !accept.is_empty() => accept,
+            _ => return Ok(ResponseFormat::Html),
+        };
+
+        accept
+            .ranked()
+            .iter()
+            .find_map(|mime| ResponseFormat::from_mime(mime.essence_str()))
+            .ok_or_else(|| ErrorNotAcceptable("Answers are available as text/html, application/json or text/plain."))
+    }
+}
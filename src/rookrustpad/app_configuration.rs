@@ -1,9 +1,16 @@
 use actix_web::web;
 use std::sync::Arc;
 
+use crate::rookrustpad::app_settings::{AppSettings, TeamSettings};
 use crate::rookrustpad::page::configure_page_handlers;
 
+use crate::rookrustpad::service::aoc::client::AocClient;
+use crate::rookrustpad::service::aoc::history::AocRunHistory;
+use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+use crate::rookrustpad::service::aoc::submission::AocSubmissionLog;
+use crate::rookrustpad::service::aoc::timer::AocTimers;
 use crate::rookrustpad::service::aoc::AocService;
+use crate::rookrustpad::service::solver_pool::SolverPool;
 
 use crate::rookrustpad::api::aoc_handler::create_aoc_handlers;
 use crate::rookrustpad::api::static_handler::create_static_handler;
@@ -12,18 +19,62 @@ use crate::rookrustpad::api::test_handlers::create_test_handlers;
 #[derive(Clone)]
 pub struct AppConfiguration {
     aoc_service: Arc<AocService>,
+    solver_pool: Arc<SolverPool>,
+    team: TeamSettings,
+}
+
+/// The aoc service with the solvers and input sources from the settings.
+pub fn create_aoc_service(settings: &AppSettings) -> AocService {
+    let input_sources = settings.input_sources().iter().map(|source| {
+        let source = source.create();
+        println!("Reading inputs from {}", source.describe());
+        source
+    }).collect();
+
+    let mut aoc_service = AocService::create_default(
+        AocInputSources::new(input_sources),
+        String::from("www/aoc_problems"));
+    aoc_service.client = AocClient::new(settings.aoc.clone());
+    aoc_service.submissions = AocSubmissionLog::new(&settings.aoc.submission_log);
+    aoc_service.timers = AocTimers::new(&settings.team.timer_file);
+    aoc_service.history = AocRunHistory::new(&settings.team.history_directory);
+
+    for external in &settings.external_solvers {
+        println!("Registering external solver {} for {}/{}/{}: {}",
+            external.variant, external.year, external.day, external.part, external.command_line());
+        aoc_service.register_external(external.clone());
+    }
+
+    aoc_service
 }
 
 impl AppConfiguration {
-    pub fn create_default() -> AppConfiguration {
+    pub fn create(settings: &AppSettings) -> AppConfiguration {
+        let pool_settings = &settings.solver_pool;
+        println!("Starting solver pool with {} workers", pool_settings.worker_count());
+
+        let aoc_service = create_aoc_service(settings);
+        match aoc_service.import_changed_statements() {
+            Ok(0) => {},
+            Ok(count) => println!("Imported {} changed statements", count),
+            Err(error) => println!("Unable to import the statements: {}", error),
+        }
+
         AppConfiguration {
-            aoc_service: Arc::new(AocService::create_default(String::from("www/aoc_input"))),
+            aoc_service: Arc::new(aoc_service),
+            solver_pool: Arc::new(SolverPool::new(
+                pool_settings.worker_count(),
+                pool_settings.max_queue,
+                pool_settings.retry_after)),
+            team: settings.team.clone(),
         }
     }
 
     pub fn configure(&self, cfg: &mut web::ServiceConfig) {
         println!("Configuring app data");
         cfg.app_data(web::Data::new(self.aoc_service.clone()));
+        cfg.app_data(web::Data::new(self.solver_pool.clone()));
+        cfg.app_data(web::Data::new(self.team.clone()));
 
         println!("Configuring all handlers");
         cfg.configure(configure_page_handlers);
@@ -0,0 +1,112 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::rookrustpad::service::aoc::client::AocClientSettings;
+use crate::rookrustpad::service::aoc::external::AocExternalSolver;
+use crate::rookrustpad::service::aoc::history::DEFAULT_HISTORY_DIRECTORY;
+use crate::rookrustpad::service::aoc::input_source::AocInputSourceSettings;
+use crate::rookrustpad::service::aoc::mock::AocMockSettings;
+use crate::rookrustpad::service::aoc::timer::DEFAULT_TIMER_FILE;
+
+const DEFAULT_SETTINGS_FILE: &str = "rookrustpad.toml";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SolverPoolSettings {
+    /// Number of solver threads, the number of cpus when not set.
+    pub workers: Option<usize>,
+    /// How many jobs may wait for a free thread before new ones are rejected.
+    pub max_queue: usize,
+    /// Seconds sent back in Retry-After when the queue is full.
+    pub retry_after: u64,
+}
+
+impl Default for SolverPoolSettings {
+    fn default() -> Self {
+        SolverPoolSettings {
+            workers: None,
+            max_queue: 64,
+            retry_after: 5,
+        }
+    }
+}
+
+impl SolverPoolSettings {
+    pub fn worker_count(&self) -> usize {
+        self.workers.unwrap_or_else(|| {
+            thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TeamSettings {
+    /// Header an authenticating proxy puts the user's name in, for example X-Forwarded-User.
+    /// Without it the user comes from ?user= alone.
+    pub identity_header: Option<String>,
+    /// Where everyone's solve timers are kept.
+    pub timer_file: String,
+    /// Where the latest runs are kept, a jsonl file for each user.
+    pub history_directory: String,
+}
+
+impl Default for TeamSettings {
+    fn default() -> Self {
+        TeamSettings {
+            identity_header: None,
+            timer_file: String::from(DEFAULT_TIMER_FILE),
+            history_directory: String::from(DEFAULT_HISTORY_DIRECTORY),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    pub solver_pool: SolverPoolSettings,
+    /// Solvers in other languages, registered next to the rust ones.
+    pub external_solvers: Vec<AocExternalSolver>,
+    /// Where inputs are read from, the first source with a file wins.
+    pub input_sources: Vec<AocInputSourceSettings>,
+    pub team: TeamSettings,
+    /// How to reach the Advent of Code website.
+    pub aoc: AocClientSettings,
+    /// The local stand-in for the AoC website run by `rookrustpad mock-aoc`.
+    pub mock_aoc: AocMockSettings,
+}
+
+impl AppSettings {
+    /// The input sources, the input directory then the embedded examples when none are set.
+    pub fn input_sources(&self) -> Vec<AocInputSourceSettings> {
+        if !self.input_sources.is_empty() {
+            return self.input_sources.clone();
+        }
+
+        vec![
+            AocInputSourceSettings::Directory { path: String::from("www/aoc_input") },
+            AocInputSourceSettings::Embedded,
+        ]
+    }
+
+    /// Reads settings from $ROOKRUSTPAD_CONFIG or rookrustpad.toml, using
+    /// defaults when the file is not there.
+    pub fn load() -> Result<AppSettings, Box<dyn Error>> {
+        let settings_file = env::var("ROOKRUSTPAD_CONFIG")
+            .unwrap_or_else(|_| String::from(DEFAULT_SETTINGS_FILE));
+
+        if !Path::new(&settings_file).exists() {
+            println!("No settings file at {}, using defaults", settings_file);
+            return Ok(AppSettings::default());
+        }
+
+        println!("Loading settings from {}", settings_file);
+        let contents = fs::read_to_string(&settings_file)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
@@ -0,0 +1,231 @@
+use std::error::Error;
+use std::fs;
+
+use crate::rookrustpad::api::mock_aoc_handler::run_mock_aoc;
+use crate::rookrustpad::app_configuration::create_aoc_service;
+use crate::rookrustpad::app_settings::AppSettings;
+use crate::rookrustpad::service::aoc::leaderboard::AocLeaderboard;
+use crate::rookrustpad::service::aoc::params::AocParamInput;
+use crate::rookrustpad::service::aoc::timer::{AocTimer, AocTimerAction};
+use crate::rookrustpad::service::aoc::AocAnswerRequest;
+
+const USAGE: &str = "Usage:
+  rookrustpad                                  Start the server
+  rookrustpad extract-examples YEAR DAY [--write] [--overwrite]
+                                               Read the example inputs and answers from a saved statement,
+                                               --write saves new ones as test datasets
+  rookrustpad import-statements [YEAR [DAY]]   Clean up the saved statement pages for reading in the app
+  rookrustpad download-inputs YEAR [DAY...]    Fetch inputs from AoC that aren't saved yet, every day when none are given
+  rookrustpad submit YEAR DAY PART [ANSWER]    Send an answer to AoC, the solver's answer for the main input
+                                               when none is given
+  rookrustpad import-leaderboard YEAR [FILE]   Save a private leaderboard's json from a file, or fetch it from AoC
+  rookrustpad timer YEAR DAY PART [ACTION] [--user=NAME]
+                                               Show a part's solve timer, or start, pause, stop or reset it
+  rookrustpad mock-aoc                         Serve a stand-in for the AoC website from the fixtures in [mock_aoc]";
+
+/// Runs a command given on the command line instead of the server.
+pub fn run(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    match args.first().map(String::as_str) {
+        Some("extract-examples") => extract_examples(&args[1..], settings),
+        Some("import-statements") => import_statements(&args[1..], settings),
+        Some("download-inputs") => download_inputs(&args[1..], settings),
+        Some("submit") => submit(&args[1..], settings),
+        Some("import-leaderboard") => import_leaderboard(&args[1..], settings),
+        Some("timer") => timer(&args[1..], settings),
+        Some("mock-aoc") => run_mock_aoc(&settings.mock_aoc),
+        Some("help") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        Some(command) => Err(format!("Unknown command {}\n{}", command, USAGE).into()),
+        None => Err(USAGE.into()),
+    }
+}
+
+/// Splits --flags from the positional arguments.
+fn split_flags(args: &[String]) -> (Vec<&str>, Vec<&str>) {
+    args.iter().map(String::as_str).partition(|arg| !arg.starts_with("--"))
+}
+
+fn year_and_day(positional: &[&str]) -> Result<(u32, u32), Box<dyn Error>> {
+    match positional {
+        [year, day] => Ok((year.parse()?, day.parse()?)),
+        _ => Err(format!("Expected a year and a day\n{}", USAGE).into()),
+    }
+}
+
+fn extract_examples(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, flags) = split_flags(args);
+    let (year, day) = year_and_day(&positional)?;
+    let write = flags.contains(&"--write");
+    let overwrite = flags.contains(&"--overwrite");
+
+    let aoc_service = create_aoc_service(settings);
+    let proposals = if write {
+        aoc_service.write_examples(year, day, overwrite)?
+    }
+    else {
+        aoc_service.extract_examples(year, day)?
+    };
+
+    if proposals.is_empty() {
+        println!("No examples found in the statement for {} day {}", year, day);
+    }
+
+    for proposal in &proposals {
+        println!();
+        println!("== Part {} ({}) ==", proposal.part, proposal.dataset);
+        println!("Expected answer: {}", proposal.expected.as_deref().unwrap_or("(none found)"));
+        match (&proposal.written_to, proposal.exists) {
+            (Some(location), _) => println!("Written to {}", location),
+            (None, true) => println!("Already exists, use --overwrite to replace it"),
+            (None, false) => println!("Not written, use --write to save it"),
+        }
+        print!("{}", proposal.input);
+    }
+
+    Ok(())
+}
+
+fn import_statements(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, _) = split_flags(args);
+    let year: Option<u32> = positional.first().map(|year| year.parse()).transpose()?;
+    let day: Option<u32> = positional.get(1).map(|day| day.parse()).transpose()?;
+
+    let aoc_service = create_aoc_service(settings);
+    let statements: Vec<_> = aoc_service.list_statements()?
+        .into_iter()
+        .filter(|s| year.is_none_or(|year| s.year == year) && day.is_none_or(|day| s.day == day))
+        .collect();
+
+    if statements.is_empty() {
+        return Err("No saved statements to import".into());
+    }
+
+    for statement in statements {
+        let text = aoc_service.import_statement(statement.year, statement.day)?;
+        println!("{} day {}: {} ({} parts) -> {}",
+            text.year, text.day, text.title, text.parts.len(),
+            aoc_service.get_statement_text_path(text.year, text.day));
+    }
+
+    Ok(())
+}
+
+fn download_inputs(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, _) = split_flags(args);
+    let year: u32 = positional.first().ok_or_else(|| format!("Expected a year\n{}", USAGE))?.parse()?;
+    let days: Vec<u32> = match &positional[1..] {
+        [] => (1..=25).collect(),
+        days => days.iter().map(|day| day.parse()).collect::<Result<_, _>>()?,
+    };
+
+    let aoc_service = create_aoc_service(settings);
+    let mut failed = 0;
+    for day in days {
+        match aoc_service.download_input(year, day) {
+            Ok(download) if download.cached => println!("{} day {}: already saved", year, day),
+            Ok(download) => println!("{} day {}: saved to {}", year, day, download.locations.join(", ")),
+            Err(error) => {
+                println!("{} day {}: {}", year, day, error);
+                failed += 1;
+            },
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} inputs could not be downloaded", failed).into());
+    }
+    Ok(())
+}
+
+fn submit(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, _) = split_flags(args);
+    let (year, day, part, answer) = match positional.as_slice() {
+        [year, day, part] => (year.parse()?, day.parse()?, part.parse()?, None),
+        [year, day, part, answer] => (year.parse()?, day.parse()?, part.parse()?, Some(answer.to_string())),
+        _ => return Err(format!("Expected a year, day and part\n{}", USAGE).into()),
+    };
+
+    let aoc_service = create_aoc_service(settings);
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let request = AocAnswerRequest {
+                year,
+                day,
+                part,
+                user: None,
+                dataset: None,
+                variant: None,
+                params: AocParamInput::new(),
+            };
+            let aoc_result = aoc_service.get_answer(&request);
+            let answer = aoc_result.result.map_err(|error| format!("The solver failed: {}", error))?;
+            println!("Solver answered {} in {:.4}s", answer, aoc_result.execution_time);
+            answer
+        },
+    };
+
+    let submission = aoc_service.submit_answer(year, day, part, &answer)?;
+    println!("{:?}: {}", submission.verdict, submission.message);
+    Ok(())
+}
+
+fn import_leaderboard(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, _) = split_flags(args);
+    let year: u32 = positional.first().ok_or_else(|| format!("Expected a year\n{}", USAGE))?.parse()?;
+
+    let aoc_service = create_aoc_service(settings);
+    let leaderboard: AocLeaderboard = match positional.get(1) {
+        Some(file) => {
+            let contents = fs::read_to_string(file).map_err(|e| format!("Unable to read {}: {}", file, e))?;
+            aoc_service.import_leaderboard(year, &contents)?
+        },
+        None => aoc_service.fetch_leaderboard(year)?,
+    };
+
+    println!("Saved the {} leaderboard to {}", year, aoc_service.get_leaderboard_path(year));
+    for (rank, member) in leaderboard.ranked().iter().enumerate() {
+        println!("{:>3}) {:>5} {:>3}* {}", rank + 1, member.local_score, member.stars, member.display_name());
+    }
+    Ok(())
+}
+
+fn print_timer(timer: &AocTimer) {
+    let seconds = timer.seconds();
+    let spent = format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match &timer.solve {
+        Some(solve) => println!("{} day {} part {}: {} spent, solved with {} from a {}",
+            timer.year, timer.day, timer.part, spent, solve.answer, solve.source),
+        None => println!("{} day {} part {}: {} spent, {:?}", timer.year, timer.day, timer.part, spent, timer.state),
+    }
+}
+
+fn timer(args: &[String], settings: &AppSettings) -> Result<(), Box<dyn Error>> {
+    let (positional, flags) = split_flags(args);
+    let user = flags.iter().find_map(|flag| flag.strip_prefix("--user=")).map(String::from);
+    let (year, day, part): (u32, u32, u8) = match positional[..] {
+        [year, day, part, ..] => (year.parse()?, day.parse()?, part.parse()?),
+        _ => return Err(format!("Expected a year, a day and a part\n{}", USAGE).into()),
+    };
+    let action = match positional.get(3).copied() {
+        None => None,
+        Some("start") => Some(AocTimerAction::Start),
+        Some("pause") => Some(AocTimerAction::Pause),
+        Some("stop") => Some(AocTimerAction::Stop),
+        Some("reset") => Some(AocTimerAction::Reset),
+        Some(action) => return Err(format!("Unknown timer action {}, expected start, pause, stop or reset", action).into()),
+    };
+
+    let aoc_service = create_aoc_service(settings);
+    let timer = match action {
+        Some(action) => aoc_service.update_timer(year, day, part, &user, action)?,
+        None => aoc_service.get_timer(year, day, part, &user)?,
+    };
+    match timer {
+        Some(timer) => print_timer(&timer),
+        None => println!("{} day {} part {}: no timer", year, day, part),
+    }
+    Ok(())
+}
@@ -1,13 +1,45 @@
 use actix_web::web;
 
+mod assets;
 mod index;
-mod aoc;
+pub mod aoc;
+mod aoc_artifact;
+mod aoc_coverage;
+mod aoc_examples;
+mod aoc_leaderboard;
+mod aoc_source;
+mod aoc_statement;
+mod aoc_team;
+mod aoc_timer;
+mod highlight;
+pub mod layout;
+mod search;
 
 pub fn configure_page_handlers(cfg: &mut web::ServiceConfig) {
     println!("Configuring page handlers");
     cfg
+        .service(assets::asset)
         .service(index::index)
+        .service(search::search)
+        .service(search::reindex)
         .service(aoc::index)
-        .service(aoc::answer_no_ds)
-        .service(aoc::answer_with_ds);
+        .service(aoc_coverage::coverage)
+        .service(aoc_source::source)
+        .service(aoc_statement::statement)
+        .service(aoc_statement::import_statement)
+        .service(aoc_team::team_page)
+        .service(aoc_examples::examples_page)
+        .service(aoc_examples::save_examples)
+        .service(aoc_leaderboard::leaderboard_page)
+        .service(aoc_leaderboard::refresh_leaderboard)
+        .service(aoc_leaderboard::paste_leaderboard_resource())
+        .service(aoc_timer::timers_page)
+        .service(aoc_timer::update_timer)
+        .service(aoc::day_page)
+        .service(aoc::download_input)
+        .service(aoc::submit)
+        .service(aoc::answer)
+        .service(aoc::answer_with_ds)
+        .service(aoc::old_answer_no_ds)
+        .service(aoc::old_answer_with_ds);
 }
@@ -1,102 +1,821 @@
+use std::error::Error;
+use std::path::Path;
 use std::sync::Arc;
 
-use actix_web::{get, web, Result as AwResult};
-use serde::{Deserialize, Serialize};
+use actix_web::http::header::{self, ContentType, HeaderValue};
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, Result as AwResult};
+use serde::Deserialize;
+use futures::stream::{self, StreamExt};
 use maud::{html, Markup};
 
-use crate::rookrustpad::service::aoc::AocService;
+use crate::rookrustpad::api::aoc_handler::{preview_answer, query_params, request_user, run_answer};
+use crate::rookrustpad::api::response_format::ResponseFormat;
+use crate::rookrustpad::page::aoc_artifact::artifacts;
+use crate::rookrustpad::page::aoc_leaderboard::leaderboard_path;
+use crate::rookrustpad::page::aoc_source::{solver_source, source_path};
+use crate::rookrustpad::page::aoc_statement::statement_path;
+use crate::rookrustpad::page::aoc_timer::{timer_controls, TIMERS_PATH};
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::params::{AocParam, AocParamInput, AocParamKind, AocParams};
+use crate::rookrustpad::service::aoc::status::{AocDayStatus, AocStar, PARTS_PER_DAY};
+use crate::rookrustpad::service::aoc::submission::{AocSubmission, AocSubmissionVerdict};
+use crate::rookrustpad::service::aoc::{AocAnswer, AocAnswerRequest, AocInput, AocProblem, AocService, AocSolver, AocVerdict};
+use crate::rookrustpad::service::solver_pool::{SolverPool, SolverPoolError};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq)]
-pub struct AocAnswerRequest {
-    year: u32,
-    day: u32,
-    part: u8,
-    dataset: Option<String>,
+const ANSWER_QUERY: &[&str] = &["format", "cached", "variant", "user"];
+
+/// Any other query parameters are passed to the solver.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocAnswerQuery {
+    format: Option<String>,
+    /// Use the latest recorded run when there is one instead of running again.
+    cached: Option<bool>,
+    /// Which implementation to run, the default one when missing.
+    variant: Option<String>,
+    /// Whose inputs to run on.
+    user: Option<String>,
+}
+
+/// Where an answer lives, the dataset is optional.
+pub fn answer_path(year: u32, day: u32, part: u8, dataset: &Option<String>) -> String {
+    match dataset {
+        Some(dataset) => format!("/aoc/{}/{}/{}/answer/{}", year, day, part, dataset),
+        None => format!("/aoc/{}/{}/{}/answer", year, day, part),
+    }
+}
+
+/// Adds ?user= to a page's path when the page is for a user.
+pub fn user_path(path: String, user: &Option<String>) -> String {
+    match user {
+        Some(user) => format!("{}?user={}", path, user),
+        None => path,
+    }
+}
+
+pub fn day_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}", year, day)
+}
+
+pub fn team_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}/team", year, day)
+}
+
+pub fn examples_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}/examples", year, day)
+}
+
+pub fn submit_path(year: u32, day: u32, part: u8) -> String {
+    format!("/aoc/{}/{}/{}/submit", year, day, part)
+}
+
+/// Milliseconds since the epoch as a UTC time.
+pub fn format_time(millis: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis as i64)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_default()
+}
+
+/// A span of seconds like "1d 2h 05m", "1h 05m 12s" or "4m 12s".
+pub fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    let (days, hours, minutes, seconds) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+    if days > 0 {
+        format!("{}{}d {}h {:02}m", sign, days, hours, minutes)
+    }
+    else if hours > 0 {
+        format!("{}{}h {:02}m {:02}s", sign, hours, minutes, seconds)
+    }
+    else {
+        format!("{}{}m {:02}s", sign, minutes, seconds)
+    }
+}
+
+pub fn download_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}/input/download", year, day)
+}
+
+/// The saved page as it was downloaded.
+pub fn statement_url(year: u32, day: u32) -> String {
+    format!("/aoc_problems/{}/Day%20{}%20-%20Advent%20of%20Code%20{}.html", year, day, year)
 }
 
+fn star_symbol(star: AocStar) -> &'static str {
+    match star {
+        AocStar::Verified => "\u{2605}",
+        AocStar::Unverified => "\u{2606}",
+        AocStar::Failed => "\u{2717}",
+        AocStar::NotRun => "\u{00b7}",
+    }
+}
+
+type AocCalendar = (u32, Vec<AocDayStatus>);
+
+fn load_calendars(aoc_service: &AocService, user: &Option<String>) -> Result<Vec<AocCalendar>, Box<dyn Error>> {
+    let mut calendars = vec![];
+    for year in aoc_service.list_years()? {
+        calendars.push((year, aoc_service.get_day_statuses(year, user)?));
+    }
+    Ok(calendars)
+}
+
+/// The calendar of every year, its stars are for the shared inputs or the user's own.
 #[get("/aoc")]
-pub async fn index(aoc_service: web::Data<Arc<AocService>>) -> AwResult<Markup> {
-    let problems = aoc_service.list_problems();
+pub async fn index(req: HttpRequest, aoc_service: web::Data<Arc<AocService>>, query: web::Query<AocDayQuery>) -> Page {
+    let user = request_user(&req, query.into_inner().user);
+    let page = Page::aoc("Advent of Code", None, None);
+
+    let calendars = match load_calendars(&aoc_service, &user) {
+        Ok(calendars) => calendars,
+        Err(error) => {
+            return page.flash(Flash::error(format!("Unable to load the calendar: {}", error)));
+        }
+    };
 
-    Ok(html! {
-        html {
-            head {
-                title { "Advent of Code" }
+    page.body(html! {
+        p {
+            a href="/aoc/coverage" { "Coverage of statements, inputs and solvers" }
+            " / "
+            a href=(TIMERS_PATH) { "Solve times" }
+        }
+        @if let Some(user) = &user {
+            p { "Stars for the inputs of " b { (user) } ". " a href="/aoc" { "Shared inputs" } }
+        }
+        @for (year, days) in &calendars {
+            h2 id=(format!("year-{}", year)) {
+                (year) " " small { a href=(leaderboard_path(*year)) { "Leaderboard" } }
             }
-            body {
-                h1 { "Advent of Code" }
-                ul {
-                    @for problem in &problems {
-                        li {
-                            a href=(format!("/aoc_problems/{}/Day {} - Advent of Code {}.html", problem.year, problem.day, problem.year)) {
-                                (format!("{} - Day {} - Part {}", problem.year, problem.day, problem.part))
+            div class="calendar" {
+                @for day in days {
+                    (calendar_day(day, &user))
+                }
+            }
+        }
+    })
+}
+
+fn calendar_day(day: &AocDayStatus, user: &Option<String>) -> Markup {
+    if day.statement.is_none() && !day.has_solver() && !day.has_inputs() {
+        return html! {
+            div class="day empty" { b { "Day " (day.day) } }
+        };
+    }
+
+    let class = if day.is_incomplete() { "day incomplete" } else { "day" };
+
+    html! {
+        div class=(class) {
+            b { a href=(user_path(day_path(day.year, day.day), user)) { "Day " (day.day) } }
+            " "
+            @for part in &day.parts {
+                span class=(format!("star {:?}", part.star)) title=(format!("Part {}: {:?}", part.part, part.star)) {
+                    (star_symbol(part.star))
+                }
+            }
+            br { }
+            @if day.statement.is_some() {
+                a href=(statement_path(day.year, day.day)) { "Statement" }
+            }
+            @else {
+                span class="missing" { "No statement" }
+            }
+            @if day.has_solver() {
+                " / "
+                a href=(source_path(day.year, day.day)) { "Source" }
+            }
+            @for part in &day.parts {
+                br { }
+                "Part " (part.part) ": "
+                @if part.implemented {
+                    a href=(user_path(answer_path(day.year, day.day, part.part, &None), user)) { "Answer" }
+                }
+                @else {
+                    span class="missing" { "no solver" }
+                }
+                @if part.datasets.is_empty() {
+                    ", " span class="missing" { "no input" }
+                }
+                @else {
+                    ", " (part.datasets.len()) " input(s)"
+                }
+                @if let Some(run) = &part.latest_run {
+                    ", " (format!("{:.3}s", run.answer.execution_time))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocDayRequest {
+    pub year: u32,
+    pub day: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocDayQuery {
+    user: Option<String>,
+}
+
+/// Runs both parts against every dataset for a day and shows them side by side.
+/// Looking isn't solving, so the runs aren't recorded and timers keep going.
+#[get("/aoc/{year}/{day}")]
+pub async fn day_page(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    request: web::Path<AocDayRequest>,
+    query: web::Query<AocDayQuery>) -> AwResult<Page>
+{
+    let AocDayRequest { year, day } = request.into_inner();
+    let user = request_user(&req, query.into_inner().user);
+    let page = Page::aoc(format!("Day {}", day), Some(year), Some(day));
+    let users = aoc_service.list_users().unwrap_or_default();
+
+    let inputs = match aoc_service.list_user_inputs(&user) {
+        Ok(inputs) => inputs,
+        Err(error) => {
+            return Ok(page.flash(Flash::error(format!("Unable to list inputs: {}", error))));
+        }
+    };
+
+    let inputs: Vec<AocInput> = inputs.into_iter().filter(|i| i.year == year && i.day == day).collect();
+    let mut datasets: Vec<Option<String>> = inputs.iter().map(|i| i.dataset.clone()).collect();
+    datasets.sort();
+    datasets.dedup();
+
+    let problems: Vec<AocProblem> = (1..=PARTS_PER_DAY).map(|part| AocProblem { year, day, part }).collect();
+    let runnable: Vec<(u8, Option<String>)> = inputs
+        .iter()
+        .filter(|i| aoc_service.problem_answers.contains_key(&AocProblem { year, day, part: i.part }))
+        .map(|i| (i.part, i.dataset.clone()))
+        .collect();
+
+    // A full solver queue only spoils the cells it happened to, the others are still shown.
+    let answers: Vec<(u8, Option<String>, Result<AocAnswer, SolverPoolError>)> = stream::iter(runnable.into_iter().map(|(part, dataset)| {
+            let request = AocAnswerRequest {
+                year,
+                day,
+                part,
+                user: user.clone(),
+                dataset: dataset.clone(),
+                variant: None,
+                params: AocParamInput::new(),
+            };
+            let run = preview_answer(&aoc_service, &solver_pool, request);
+            async move { (part, dataset, run.await) }
+        }))
+        .buffered(solver_pool.workers())
+        .collect()
+        .await;
+
+    let cell = |part: u8, dataset: &Option<String>| -> Markup {
+        let aoc_result = answers.iter().find(|(p, d, _)| *p == part && d == dataset).map(|(_, _, run)| run);
+        let implemented = aoc_service.problem_answers.contains_key(&AocProblem { year, day, part });
+
+        match aoc_result {
+            None if !implemented => html! { td class="cell missing" { "no solver" } },
+            None => html! { td class="cell empty" { "no input" } },
+            Some(Err(error)) => html! {
+                td class=(format!("cell {:?}", AocVerdict::Failed)) {
+                    a href=(user_path(answer_path(year, day, part, dataset), &user)) { span { (error) } }
+                }
+            },
+            Some(Ok(aoc_result)) => {
+                let verdict = aoc_result.verdict();
+                html! {
+                    td class=(format!("cell {:?}", verdict)) {
+                        a href=(user_path(answer_path(year, day, part, dataset), &user)) {
+                            @match &aoc_result.result {
+                                Ok(result) => code { (result) },
+                                Err(error) => span { (error) },
                             }
-                            " / "
-                            a href=(format!("/aoc/answer/{}/{}/{}", problem.year, problem.day, problem.part)) {
-                                "[Answer]"
+                        }
+                        br { }
+                        (format!("{:?}", verdict))
+                        @if verdict == AocVerdict::Incorrect {
+                            @if let Some(expected) = &aoc_result.expected {
+                                ", expected " code { (expected) }
                             }
-                            " / " 
-                            a href=(format!("/api/aoc/answer/{}/{}/{}", problem.year, problem.day, problem.part)) {
-                                "[Answer API]"
+                        }
+                        br { }
+                        small { (format!("{:.4}s", aoc_result.execution_time)) }
+                    }
+                }
+            }
+        }
+    };
+
+    let has_statement = Path::new(&aoc_service.get_statement_path(year, day)).exists();
+    let timers = aoc_service.list_timers(&user).unwrap_or_default();
+
+    Ok(page.script("/assets/timer.js").body(html! {
+        @if has_statement {
+            p {
+                a href=(statement_path(year, day)) { "Statement" }
+                " / "
+                a href=(examples_path(year, day)) { "Examples from the statement" }
+            }
+        }
+        @if !users.is_empty() {
+            form class="user" method="get" action=(day_path(year, day)) {
+                label for="user" { "Inputs " }
+                select id="user" name="user" onchange="this.form.submit()" {
+                    option value="" selected[user.is_none()] { "(shared)" }
+                    @for name in &users {
+                        option value=(name) selected[user.as_ref() == Some(name)] { (name) }
+                    }
+                }
+                " "
+                noscript { button type="submit" { "Show" } " " }
+                a href=(team_path(year, day)) { "Everyone's answers" }
+            }
+        }
+        div class="timers-day" {
+            @for problem in &problems {
+                @let timer = timers.iter().find(|t| t.day == day && t.year == year && t.part == problem.part && t.user == user);
+                (timer_controls(year, day, problem.part, &user, timer))
+            }
+        }
+        @if user.is_none() && !datasets.contains(&None) {
+            form method="post" action=(download_path(year, day)) {
+                button type="submit" { "Download the input from AoC" }
+            }
+        }
+        @if datasets.is_empty() {
+            p { "There are no inputs for this day." }
+        }
+        @else {
+            table class="day-matrix" {
+                tr {
+                    th { "Dataset" }
+                    @for problem in &problems {
+                        th { "Part " (problem.part) }
+                    }
+                }
+                @for dataset in &datasets {
+                    @let metadata = inputs.iter().find(|i| &i.dataset == dataset).and_then(|i| i.metadata.as_ref());
+                    tr {
+                        th title=[metadata.and_then(|m| m.description.as_ref())] {
+                            (dataset_label(dataset))
+                            @if let Some(source) = metadata.and_then(|m| m.source.as_ref()) {
+                                br;
+                                small { (source) }
                             }
                         }
+                        @for problem in &problems {
+                            (cell(problem.part, dataset))
+                        }
                     }
                 }
             }
         }
+    }))
+}
+
+/// Fetches the day's input with the configured session, unless it is already saved.
+#[post("/aoc/{year}/{day}/input/download")]
+pub async fn download_input(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocDayRequest>) -> AwResult<HttpResponse> {
+    let AocDayRequest { year, day } = request.into_inner();
+
+    // Requests are throttled, so this can wait a while.
+    let downloaded = web::block(move || aoc_service.download_input(year, day).map_err(|e| e.to_string())).await?;
+
+    Ok(match downloaded {
+        Ok(download) if download.cached => Flash::info("The input was already saved.").redirect(&day_path(year, day)),
+        Ok(download) => Flash::info(format!("Saved the input to {}.", download.locations.join(", "))).redirect(&day_path(year, day)),
+        Err(error) => Flash::error(format!("Unable to download the input: {}", error)).redirect(&day_path(year, day)),
     })
 }
 
-#[get("/aoc/answer/{year}/{day}/{part}")]
-pub async fn answer_no_ds(
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocSubmitForm {
+    answer: String,
+}
+
+/// Sends the answer to AoC, the previous attempts can stop it from being sent.
+#[post("/aoc/{year}/{day}/{part}/submit")]
+pub async fn submit(
     aoc_service: web::Data<Arc<AocService>>,
-    request: web::Path<AocAnswerRequest>) -> AwResult<Markup>
+    request: web::Path<(u32, u32, u8)>,
+    form: web::Form<AocSubmitForm>) -> AwResult<HttpResponse>
 {
-    answer_aoc(aoc_service, request).await
+    let (year, day, part) = request.into_inner();
+    let submitted_answer = form.into_inner().answer;
+    let page_path = format!("{}?cached=true", answer_path(year, day, part, &None));
+
+    let submitted = web::block(move || aoc_service.submit_answer(year, day, part, &submitted_answer).map_err(|e| e.to_string())).await?;
+
+    Ok(match submitted {
+        Ok(submission) if submission.verdict == AocSubmissionVerdict::Correct =>
+            Flash::info(format!("Correct! {} is saved as the expected answer.", submission.answer)).redirect(&page_path),
+        Ok(submission) => Flash::error(format!("{:?}: {}", submission.verdict, submission.message)).redirect(&page_path),
+        Err(error) => Flash::error(format!("Not submitted: {}", error)).redirect(&page_path),
+    })
 }
 
-#[get("/aoc/answer/{year}/{day}/{part}/{dataset}")]
+#[get("/aoc/{year}/{day}/{part}/answer")]
+pub async fn answer(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    request: web::Path<AocAnswerRequest>,
+    query: web::Query<AocAnswerQuery>) -> AwResult<HttpResponse>
+{
+    answer_aoc(req, aoc_service, solver_pool, request, query).await
+}
+
+#[get("/aoc/{year}/{day}/{part}/answer/{dataset}")]
 pub async fn answer_with_ds(
+    req: HttpRequest,
     aoc_service: web::Data<Arc<AocService>>,
-    request: web::Path<AocAnswerRequest>) -> AwResult<Markup>
+    solver_pool: web::Data<Arc<SolverPool>>,
+    request: web::Path<AocAnswerRequest>,
+    query: web::Query<AocAnswerQuery>) -> AwResult<HttpResponse>
 {
-    answer_aoc(aoc_service, request).await
+    answer_aoc(req, aoc_service, solver_pool, request, query).await
+}
+
+#[get("/aoc/answer/{year}/{day}/{part}")]
+pub async fn old_answer_no_ds(req: HttpRequest, request: web::Path<AocAnswerRequest>) -> HttpResponse {
+    old_answer_redirect(&req, &request)
+}
+
+#[get("/aoc/answer/{year}/{day}/{part}/{dataset}")]
+pub async fn old_answer_with_ds(req: HttpRequest, request: web::Path<AocAnswerRequest>) -> HttpResponse {
+    old_answer_redirect(&req, &request)
+}
+
+fn old_answer_redirect(req: &HttpRequest, request: &AocAnswerRequest) -> HttpResponse {
+    Flash::info("Answer pages have moved, please update any bookmarks.")
+        .redirect(&moved_answer_path(req, request, None))
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct AocDatasetQuery {
+    dataset: Option<String>,
+}
+
+/// Where an old answer link points now. A ?dataset= moves into the path, the rest of the
+/// query is kept, and format replaces any format the link had.
+pub fn moved_answer_path(req: &HttpRequest, request: &AocAnswerRequest, format: Option<&str>) -> String {
+    let dataset = request.dataset.clone().or_else(|| {
+        web::Query::<AocDatasetQuery>::from_query(req.query_string()).ok()?.into_inner().dataset
+    });
+
+    let mut query: Vec<String> = format.map(|format| format!("format={}", format)).into_iter().collect();
+    query.extend(req.query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            name != "dataset" && !(format.is_some() && name == "format")
+        })
+        .map(String::from));
+
+    let path = answer_path(request.year, request.day, request.part, &dataset);
+    if query.is_empty() {
+        path
+    }
+    else {
+        format!("{}?{}", path, query.join("&"))
+    }
+}
+
+pub fn redirect(location: String) -> HttpResponse {
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, location))
+        .finish()
 }
 
 async fn answer_aoc(
+    req: HttpRequest,
     aoc_service: web::Data<Arc<AocService>>,
-    request: web::Path<AocAnswerRequest>) -> AwResult<Markup>
+    solver_pool: web::Data<Arc<SolverPool>>,
+    request: web::Path<AocAnswerRequest>,
+    query: web::Query<AocAnswerQuery>) -> AwResult<HttpResponse>
 {
-    let aoc_result = aoc_service.get_answer(request.year, request.day, request.part, &request.dataset);
-
-    Ok(html! {
-        html {
-            head {
-                title { "Advent of Code Answer" }
-            }
-            body {
-                h1 { (format!("AOC {} - Day {} - Part {}", request.year, request.day, request.part)) }
-                div {
-                    div {
-                        @match &aoc_result.result {
-                            Err(error) => {
-                                b { "Error:"  } (error)
-                            },
-                            Ok(result) => {
-                                b { "Result:" } (result)
+    let format = ResponseFormat::negotiate(&req, query.format.as_deref())?;
+
+    let params = query_params(&req, ANSWER_QUERY)?;
+    let user = request_user(&req, query.user.clone());
+
+    // A cached run only counts when it ran the variant and parameters asked for,
+    // the default variant when none is given.
+    let wanted = aoc_service
+        .get_solver_variant(request.year, request.day, request.part, &query.variant)
+        .and_then(|solver| Some((solver.variant.clone(), AocParams::resolve(solver.params, &params).ok()?)));
+    let cached_run = match (query.cached, wanted) {
+        (Some(true), Some((variant, wanted_params))) => aoc_service.history
+            .latest(request.year, request.day, request.part, &user, &request.dataset)
+            .filter(|run| run.answer.variant.as_ref() == Some(&variant) && run.answer.params == wanted_params),
+        _ => None,
+    };
+
+    let aoc_result = match cached_run {
+        Some(run) => run.answer,
+        None => {
+            let request = AocAnswerRequest { user: user.clone(), variant: query.variant.clone(), params, ..request.clone() };
+            run_answer(&aoc_service, &solver_pool, request).await?
+        },
+    };
+
+    let mut response = match format {
+        ResponseFormat::Html => {
+            // The page offers every dataset for the part, a listing error just leaves the one asked for.
+            let datasets: Vec<(Option<String>, Option<String>)> = match aoc_service.list_user_inputs(&user) {
+                Ok(inputs) => inputs
+                    .into_iter()
+                    .filter(|i| i.year == request.year && i.day == request.day && i.part == request.part)
+                    .map(|i| (i.dataset, i.metadata.and_then(|m| m.description)))
+                    .collect(),
+                Err(_) => vec![(request.dataset.clone(), None)],
+            };
+            let variants = aoc_service.list_variants(request.year, request.day, request.part);
+            let solver = aoc_service.get_solver_variant(request.year, request.day, request.part, &aoc_result.variant);
+            let submissions = aoc_service.list_submissions(request.year, request.day, request.part).unwrap_or_else(|error| {
+                println!("Unable to read the submissions: {}", error);
+                vec![]
+            });
+            let request = AocAnswerRequest { user, ..request.into_inner() };
+            answer_page(&request, &datasets, &variants, solver, &aoc_result, &submissions).respond_to(&req)
+        },
+        ResponseFormat::Json =>
+            HttpResponse::Ok().json(&aoc_result),
+        ResponseFormat::Text =>
+            HttpResponse::Ok()
+                .content_type(ContentType::plaintext())
+                .body(answer_text(&aoc_result)),
+    };
+
+    // Answers are worked out on every request, so caches must always come back to us.
+    let headers = response.headers_mut();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    headers.insert(header::VARY, HeaderValue::from_static("Accept"));
+
+    Ok(response)
+}
+
+fn answer_text(aoc_result: &AocAnswer) -> String {
+    match &aoc_result.result {
+        Ok(result) => format!("{}\n", result),
+        Err(error) => format!("Error: {}\n", error),
+    }
+}
+
+fn dataset_label(dataset: &Option<String>) -> &str {
+    dataset.as_deref().unwrap_or("(main input)")
+}
+
+/// Runs the page again with the parameters, filled in with the ones the shown answer used.
+fn params_form(request: &AocAnswerRequest, variants: &[String], solver: &AocSolver, aoc_result: &AocAnswer) -> Markup {
+    html! {
+        form id="params" class="params" method="get" action=(answer_path(request.year, request.day, request.part, &request.dataset)) {
+            @if variants.len() > 1 {
+                input type="hidden" name="variant" value=(solver.variant);
+            }
+            @if let Some(user) = &request.user {
+                input type="hidden" name="user" value=(user);
+            }
+            @for param in solver.params {
+                @let value = aoc_result.params.0.get(param.name).cloned().unwrap_or_else(|| param.default_value());
+                label title=(param.help) {
+                    (param.name) " "
+                    (param_input(param, &value.to_string()))
+                }
+                " "
+            }
+            button type="submit" { "Run with parameters" }
+        }
+    }
+}
+
+fn param_input(param: &AocParam, value: &str) -> Markup {
+    html! {
+        @match param.kind {
+            AocParamKind::Integer { min, max, .. } => {
+                input type="number" name=(param.name) value=(value) min=(min) max=(max) step="1";
+            },
+            AocParamKind::Float { min, max, .. } => {
+                input type="number" name=(param.name) value=(value) min=(min) max=(max) step="any";
+            },
+            AocParamKind::Bool { .. } => {
+                select name=(param.name) {
+                    option value="true" selected[value == "true"] { "true" }
+                    option value="false" selected[value == "false"] { "false" }
+                }
+            },
+            AocParamKind::Text { .. } => {
+                input type="text" name=(param.name) value=(value);
+            },
+        }
+    }
+}
+
+fn answer_page(
+    request: &AocAnswerRequest,
+    datasets: &[(Option<String>, Option<String>)],
+    variants: &[String],
+    solver: Option<&AocSolver>,
+    aoc_result: &AocAnswer,
+    submissions: &[AocSubmission]) -> Page
+{
+    // Only the main input's answer is the one AoC checks.
+    let submittable = request.user.is_none() && request.dataset.is_none() && aoc_result.result.is_ok();
+
+    let title = match &request.user {
+        Some(user) => format!("Day {} - Part {} for {}", request.day, request.part, user),
+        None => format!("Day {} - Part {}", request.day, request.part),
+    };
+    Page::aoc(title, Some(request.year), Some(request.day))
+        .crumb(format!("Part {}", request.part), None)
+        .script("/assets/answer.js")
+        .body(html! {
+            form id="runner" class="runner"
+                data-year=(request.year)
+                data-day=(request.day)
+                data-part=(request.part)
+                data-user=[request.user.as_ref()]
+            {
+                label for="dataset" { "Dataset " }
+                select id="dataset" name="dataset" {
+                    @for (dataset, description) in datasets {
+                        option value=(dataset.as_deref().unwrap_or("")) selected[dataset == &request.dataset] {
+                            (dataset_label(dataset))
+                            @if let Some(description) = description {
+                                " - " (description)
                             }
                         }
                     }
-                    br { }
-                    b { "Execution Time: " } (aoc_result.execution_time) " seconds."
-                    br { }
-                    b { "Execution Log" }
-                    br { }
-                    textarea rows="15" cols="150" {
-                        (&aoc_result.log)
+                }
+                @if variants.len() > 1 {
+                    " "
+                    label for="variant" { "Variant " }
+                    select id="variant" name="variant" {
+                        @for variant in variants {
+                            option value=(variant) selected[aoc_result.variant.as_ref() == Some(variant)] { (variant) }
+                        }
                     }
                 }
+                " "
+                button type="button" id="run" title="Show the latest run, running it if there is none" { "Run" }
+                " "
+                button type="button" id="rerun" title="Run the solver again" { "Re-run" }
+                " "
+                button type="button" id="benchmark" { "Benchmark" }
+                " "
+                input type="number" id="iterations" min="1" max="1000" value="10" size="4" title="Benchmark iterations";
+                " times"
+                @if variants.len() > 1 {
+                    " "
+                    button type="button" id="compare" title="Benchmark every variant on this dataset" { "Compare variants" }
+                }
             }
-        }
-    })
-}
\ No newline at end of file
+            div id="status" class="runner-status" { }
+            @if let Some(solver) = solver.filter(|s| !s.params.is_empty()) {
+                (params_form(request, variants, solver, aoc_result))
+            }
+            div class="answer" {
+                @match &aoc_result.result {
+                    Err(error) => {
+                        b { "Error: " } span id="answer" class="error" { (error) }
+                    },
+                    Ok(result) => {
+                        b { "Result: " } span id="answer" { (result) }
+                    }
+                }
+                " "
+                button type="button" id="copy" title="Copy the answer" { "Copy" }
+                " "
+                form id="submit" class="submit" method="post" action=(submit_path(request.year, request.day, request.part)) hidden[!submittable] {
+                    input type="hidden" id="submit-answer" name="answer" value=(aoc_result.result.as_deref().unwrap_or(""));
+                    button type="submit" title="Send this answer to Advent of Code" { "Submit to AoC" }
+                }
+            }
+            div {
+                b { "Verdict: " } span id="verdict" { (format!("{:?}", aoc_result.verdict())) }
+                span id="expected" {
+                    @if let Some(expected) = &aoc_result.expected {
+                        " (expected " (expected) ")"
+                    }
+                }
+            }
+            div {
+                b { "Execution Time: " } span id="execution-time" { (aoc_result.execution_time) } " seconds."
+            }
+            div id="benchmark-result" { }
+            div id="comparison" { }
+            @if !submissions.is_empty() {
+                h3 { "Submissions" }
+                table class="submissions" {
+                    tr { th { "Time" } th { "Answer" } th { "Verdict" } th { "Reply" } }
+                    @for submission in submissions.iter().rev() {
+                        tr {
+                            td { (format_time(submission.submitted_at)) }
+                            td { code { (submission.answer) } }
+                            td class=(if submission.verdict == AocSubmissionVerdict::Correct { "Correct" } else if submission.verdict.is_wrong() { "Incorrect" } else { "" }) {
+                                (format!("{:?}", submission.verdict))
+                            }
+                            td { small { (submission.message) } }
+                        }
+                    }
+                }
+            }
+            (artifacts(&aoc_result.artifacts))
+            b { "Execution Log" }
+            br { }
+            textarea id="log" rows="15" cols="150" readonly {
+                (&aoc_result.log)
+            }
+            @if let Some(solver) = solver {
+                (solver_source(solver))
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::history::AocRunHistory;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+    use crate::rookrustpad::service::aoc::timer::AocTimers;
+
+    fn aoc_service(directory: &tempfile::TempDir) -> AocService {
+        let path = |name: &str| directory.path().join(name).to_string_lossy().into_owned();
+        let source = DirectoryInputSource::new("www/aoc_input");
+        let mut aoc_service = AocService::create_default(AocInputSources::new(vec![Box::new(source)]), String::from("www/aoc_problems"));
+        aoc_service.timers = AocTimers::new(&path("timers.json"));
+        aoc_service.history = AocRunHistory::new(&path("history"));
+        aoc_service
+    }
+
+    #[actix_web::test]
+    async fn day_page_shows_a_full_queue_in_the_cells() {
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = aoc_service(&directory);
+        let solver_pool = Arc::new(SolverPool::new(1, 0, 5));
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(Arc::new(aoc_service)))
+            .app_data(web::Data::new(solver_pool.clone()))
+            .service(day_page)).await;
+
+        // The only solver thread is busy and nothing may wait for it.
+        let (release, released) = channel::<()>();
+        let blocking = solver_pool.submit(move || released.recv().unwrap()).unwrap();
+        let response = test::call_service(&app, test::TestRequest::get().uri("/aoc/2022/11").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(body.contains("Solver queue is full, retry in 5 seconds."), "{}", body);
+        assert!(body.contains("cell Failed"), "{}", body);
+
+        release.send(()).unwrap();
+        blocking.await.unwrap();
+        let response = test::call_service(&app, test::TestRequest::get().uri("/aoc/2022/11").to_request()).await;
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(!body.contains("Solver queue is full"), "{}", body);
+        assert!(body.contains("cell Correct"), "{}", body);
+    }
+
+    #[actix_web::test]
+    async fn cached_answers_are_of_the_default_variant_when_none_is_asked_for() {
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = Arc::new(aoc_service(&directory));
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(aoc_service.clone()))
+            .app_data(web::Data::new(Arc::new(SolverPool::new(1, 4, 5))))
+            .service(answer_with_ds)).await;
+        let cached = |variant: &str| {
+            let request = AocAnswerRequest {
+                year: 2022,
+                day: 11,
+                part: 2,
+                user: None,
+                dataset: Some(String::from("test")),
+                variant: Some(variant.to_string()),
+                params: AocParamInput::new(),
+            };
+            let mut run = aoc_service.compute_answer(&request);
+            run.result = Ok(format!("cached {}", variant));
+            aoc_service.history.record(&run);
+        };
+        let get = || test::TestRequest::get().uri("/aoc/2022/11/2/answer/test?cached=true&format=json").to_request();
+
+        // The other variant's run is newer, but naive is the default.
+        cached("naive");
+        cached("vec_soa");
+        let aoc_result: AocAnswer = test::call_and_read_body_json(&app, get()).await;
+        assert_eq!(aoc_result.variant.as_deref(), Some("naive"));
+        assert_eq!(aoc_result.result, Ok(String::from("2713310158")));
+
+        cached("naive");
+        let aoc_result: AocAnswer = test::call_and_read_body_json(&app, get()).await;
+        assert_eq!(aoc_result.result, Ok(String::from("cached naive")));
+    }
+}
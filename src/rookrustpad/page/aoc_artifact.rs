@@ -0,0 +1,78 @@
+use maud::{html, Markup, PreEscaped};
+
+use crate::rookrustpad::service::aoc::output::AocArtifact;
+
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 200.0;
+const CHART_PADDING: f64 = 8.0;
+
+/// Draws the points as a line scaled to fit the chart. answer.js draws the same chart after a run.
+fn series_chart(points: &[(f64, f64)]) -> Markup {
+    let bounds = |values: &mut dyn Iterator<Item = f64>| {
+        values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+    };
+    let (min_x, max_x) = bounds(&mut points.iter().map(|p| p.0));
+    let (min_y, max_y) = bounds(&mut points.iter().map(|p| p.1));
+
+    let scale = |v: f64, lo: f64, hi: f64, size: f64| {
+        if hi > lo { (v - lo) / (hi - lo) * (size - 2.0 * CHART_PADDING) } else { 0.0 }
+    };
+
+    let line = points.iter()
+        .map(|(x, y)| format!("{:.1},{:.1}",
+            CHART_PADDING + scale(*x, min_x, max_x, CHART_WIDTH),
+            CHART_HEIGHT - CHART_PADDING - scale(*y, min_y, max_y, CHART_HEIGHT)))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html! {
+        @if points.is_empty() {
+            p class="series-range" { "No points." }
+        }
+        @else {
+            svg class="series-chart" viewBox=(format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)) width=(CHART_WIDTH) height=(CHART_HEIGHT) {
+                polyline points=(line) fill="none" stroke="currentColor" stroke-width="2" { }
+            }
+            div class="series-range" {
+                "x " (min_x) " to " (max_x) ", y " (min_y) " to " (max_y)
+            }
+        }
+    }
+}
+
+fn artifact(artifact: &AocArtifact) -> Markup {
+    html! {
+        section class="artifact" {
+            h3 { (artifact.title()) }
+            @match artifact {
+                AocArtifact::Table { rows, .. } => {
+                    table {
+                        @for (key, value) in rows {
+                            tr { th { (key) } td { (value) } }
+                        }
+                    }
+                },
+                AocArtifact::Grid { rows, .. } => {
+                    pre class="grid" { (rows.join("\n")) }
+                },
+                AocArtifact::Series { points, .. } => {
+                    (series_chart(points))
+                },
+                AocArtifact::Svg { svg, .. } => {
+                    div class="svg" { (PreEscaped(svg)) }
+                },
+            }
+        }
+    }
+}
+
+/// A solver's artifacts, in the order it returned them.
+pub fn artifacts(artifacts: &[AocArtifact]) -> Markup {
+    html! {
+        div id="artifacts" {
+            @for a in artifacts {
+                (artifact(a))
+            }
+        }
+    }
+}
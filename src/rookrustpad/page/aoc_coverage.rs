@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use actix_web::{get, web};
+use maud::{html, Markup};
+
+use crate::rookrustpad::page::aoc::{answer_path, day_path};
+use crate::rookrustpad::page::aoc_statement::statement_path;
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::{AocInput, AocService};
+
+fn input_label(input: &AocInput) -> String {
+    match &input.dataset {
+        Some(dataset) => format!("{} Day {} Part {} ({})", input.year, input.day, input.part, dataset),
+        None => format!("{} Day {} Part {}", input.year, input.day, input.part),
+    }
+}
+
+fn section(title: &str, help: &str, items: Vec<Markup>) -> Markup {
+    html! {
+        section class="coverage-section" {
+            h2 {
+                (title) " "
+                @if items.is_empty() {
+                    span class="ok" { "\u{2713}" }
+                }
+                @else {
+                    span class="missing" { "(" (items.len()) ")" }
+                }
+            }
+            p class="help" { (help) }
+            @if !items.is_empty() {
+                ul {
+                    @for item in &items {
+                        li { (item) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[get("/aoc/coverage")]
+pub async fn coverage(aoc_service: web::Data<Arc<AocService>>) -> Page {
+    let page = Page::aoc("Coverage", None, None);
+
+    let coverage = match aoc_service.get_coverage() {
+        Ok(coverage) => coverage,
+        Err(error) => {
+            return page.flash(Flash::error(format!("Unable to work out coverage: {}", error)));
+        }
+    };
+
+    let page = if coverage.is_complete() {
+        page.flash(Flash::info("Every statement, solver and input lines up."))
+    }
+    else {
+        page
+    };
+
+    page.body(html! {
+        p {
+            (coverage.statement_count) " statements, "
+            (coverage.solver_count) " solvers, "
+            (coverage.input_count) " inputs and "
+            (coverage.expected_answer_count) " expected answers. "
+            a href="/api/aoc/coverage" { "JSON" }
+        }
+
+        (section(
+            "Statements without solvers",
+            "Saved puzzle statements that no solver has been registered for.",
+            coverage.statements_without_solvers.iter().map(|s| html! {
+                a href=(statement_path(s.year, s.day)) { (s.year) " Day " (s.day) }
+            }).collect()))
+
+        (section(
+            "Broken statement links",
+            "Solvers whose statement file is missing, so the calendar has nothing to link to.",
+            coverage.solvers_without_statements.iter().map(|p| html! {
+                a href=(day_path(p.year, p.day)) { (p.year) " Day " (p.day) " Part " (p.part) }
+                " expects " code { (aoc_service.get_statement_path(p.year, p.day)) }
+            }).collect()))
+
+        (section(
+            "Solvers without inputs",
+            "Registered solvers with no input file to run against.",
+            coverage.solvers_without_inputs.iter().map(|p| html! {
+                a href=(day_path(p.year, p.day)) { (p.year) " Day " (p.day) " Part " (p.part) }
+            }).collect()))
+
+        (section(
+            "Inputs without solvers",
+            "Input files for a part that has no registered solver.",
+            coverage.inputs_without_solvers.iter().map(|i| html! {
+                (input_label(i)) " " code { (i.input) }
+            }).collect()))
+
+        (section(
+            "Inputs without expected answers",
+            "Answers for these inputs can be run but not verified.",
+            coverage.inputs_without_expected_answers.iter().map(|i| html! {
+                a href=(answer_path(i.year, i.day, i.part, &i.dataset)) { (input_label(i)) }
+            }).collect()))
+
+        (section(
+            "Expected answers without inputs",
+            "Expected answers in sidecars for an input file which does not exist.",
+            coverage.expected_answers_without_inputs.iter().map(|e| html! {
+                (e.year) " Day " (e.day) " Part " (e.part)
+                @if let Some(dataset) = &e.dataset { " (" (dataset) ")" }
+                " = " code { (e.answer) }
+            }).collect()))
+    })
+}
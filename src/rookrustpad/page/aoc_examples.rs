@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpResponse, Result as AwResult};
+use maud::html;
+use serde::Deserialize;
+
+use crate::rookrustpad::page::aoc::{answer_path, day_path, examples_path, AocDayRequest};
+use crate::rookrustpad::page::aoc_statement::statement_path;
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::AocService;
+
+/// Admin: shows the examples found in a day's saved statement before they are saved.
+#[get("/aoc/{year}/{day}/examples")]
+pub async fn examples_page(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocDayRequest>) -> Page {
+    let AocDayRequest { year, day } = request.into_inner();
+    let page = Page::aoc(format!("Day {} - Examples", day), Some(year), Some(day)).crumb("Examples", None);
+
+    let proposals = match aoc_service.extract_examples(year, day) {
+        Ok(proposals) => proposals,
+        Err(error) => {
+            return page.flash(Flash::error(format!("Unable to read the statement: {}", error)));
+        }
+    };
+
+    page.body(html! {
+        p {
+            "Example inputs and answers found in the "
+            a href=(statement_path(year, day)) { "saved statement" }
+            ". Saving writes them as datasets with the answers in their sidecars."
+        }
+        @if proposals.is_empty() {
+            p class="missing" { "No examples were found." }
+        }
+        @for proposal in &proposals {
+            h2 { "Part " (proposal.part) " (" (proposal.dataset) ")" }
+            p {
+                b { "Expected answer: " }
+                @match &proposal.expected {
+                    Some(expected) => code { (expected) },
+                    None => span class="missing" { "none found" },
+                }
+                @if proposal.exists {
+                    " - "
+                    a href=(answer_path(year, day, proposal.part, &Some(proposal.dataset.clone()))) { "already saved" }
+                }
+            }
+            pre { (proposal.input) }
+        }
+        @if !proposals.is_empty() {
+            form method="post" action=(examples_path(year, day)) {
+                label { input type="checkbox" name="overwrite" value="true"; " Replace saved examples" }
+                " "
+                button type="submit" { "Save examples" }
+            }
+        }
+    })
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocExamplesForm {
+    overwrite: Option<bool>,
+}
+
+/// Admin: saves the examples from a day's statement.
+#[post("/aoc/{year}/{day}/examples")]
+pub async fn save_examples(
+    aoc_service: web::Data<Arc<AocService>>,
+    request: web::Path<AocDayRequest>,
+    form: web::Form<AocExamplesForm>) -> AwResult<HttpResponse>
+{
+    let AocDayRequest { year, day } = request.into_inner();
+    let overwrite = form.overwrite.unwrap_or(false);
+
+    let saved = web::block(move || aoc_service.write_examples(year, day, overwrite).map_err(|e| e.to_string())).await?;
+    Ok(match saved {
+        Ok(proposals) => {
+            let written = proposals.iter().filter(|p| p.written_to.is_some()).count();
+            Flash::info(format!("Saved {} of {} examples.", written, proposals.len())).redirect(&day_path(year, day))
+        },
+        Err(error) => {
+            Flash::error(format!("Unable to save the examples: {}", error)).redirect(&examples_path(year, day))
+        }
+    })
+}
@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use actix_web::error::InternalError;
+use actix_web::{get, post, web, HttpResponse, Resource, Result as AwResult};
+use maud::{html, Markup};
+use serde::Deserialize;
+
+use crate::rookrustpad::page::aoc::{day_path, format_duration, format_time};
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::leaderboard::{AocLeaderboard, AocLeaderboardMember};
+use crate::rookrustpad::service::aoc::status::{unlock_time, DAYS_PER_YEAR, PARTS_PER_DAY};
+use crate::rookrustpad::service::aoc::{AocProblem, AocService};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocYearRequest {
+    year: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocLeaderboardForm {
+    json: String,
+}
+
+/// Room for a full leaderboard of 200 members once it is url encoded, forms are 16KB by default.
+const PASTE_LIMIT: usize = 4 * 1024 * 1024;
+
+pub fn leaderboard_path(year: u32) -> String {
+    format!("/aoc/{}/leaderboard", year)
+}
+
+/// Seconds from a day's unlock to a star.
+fn solve_seconds(year: u32, day: u32, star_ts: i64) -> Option<i64> {
+    Some(star_ts - unlock_time(year, day)?.timestamp())
+}
+
+fn star_cell(year: u32, day: u32, member: &AocLeaderboardMember) -> Markup {
+    let times: Vec<String> = (1..=PARTS_PER_DAY)
+        .filter_map(|part| {
+            let star_ts = member.star_time(day, part)?;
+            let after_unlock = solve_seconds(year, day, star_ts).map(format_duration).unwrap_or_default();
+            Some(format!("Part {}: {} ({} after unlock)", part, format_time(star_ts as u64 * 1000), after_unlock))
+        })
+        .collect();
+
+    let (class, symbol) = match times.len() {
+        0 => ("star none", "·"),
+        1 => ("star silver", "*"),
+        _ => ("star gold", "*"),
+    };
+    html! {
+        td class=(class) title=(times.join("\n")) { (symbol) }
+    }
+}
+
+fn day_table(aoc_service: &AocService, leaderboard: &AocLeaderboard, year: u32, day: u32) -> Markup {
+    let mut solvers: Vec<&AocLeaderboardMember> = leaderboard.members
+        .values()
+        .filter(|m| m.star_time(day, 1).is_some())
+        .collect();
+    solvers.sort_by_key(|m| (m.star_time(day, 2).unwrap_or(i64::MAX), m.star_time(day, 1)));
+
+    html! {
+        details class="leaderboard-day" {
+            summary {
+                b { "Day " (day) } " "
+                @for part in 1..=PARTS_PER_DAY {
+                    @if aoc_service.problem_answers.contains_key(&AocProblem { year, day, part }) {
+                        span class="implemented" { "part " (part) " solver" } " "
+                    }
+                    @else {
+                        span class="missing" { "no part " (part) " solver" } " "
+                    }
+                }
+                " · " a href=(day_path(year, day)) { "day page" }
+            }
+            table class="leaderboard" {
+                tr { th { "Member" } th { "Part 1" } th { "Part 2" } th { "Part 1 to 2" } }
+                @for member in &solvers {
+                    @let first = member.star_time(day, 1);
+                    @let second = member.star_time(day, 2);
+                    tr {
+                        td { (member.display_name()) }
+                        @for star_ts in [first, second] {
+                            td title=[star_ts.map(|ts| format_time(ts as u64 * 1000))] {
+                                @if let Some(seconds) = star_ts.and_then(|ts| solve_seconds(year, day, ts)) {
+                                    (format_duration(seconds))
+                                }
+                            }
+                        }
+                        td {
+                            @if let (Some(first), Some(second)) = (first, second) {
+                                (format_duration(second - first))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A private leaderboard with each member's stars and how long each day took them.
+#[get("/aoc/{year}/leaderboard")]
+pub async fn leaderboard_page(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocYearRequest>) -> Page {
+    let year = request.year;
+    let page = Page::aoc(format!("{} Leaderboard", year), Some(year), None).crumb("Leaderboard", None);
+
+    let board = match aoc_service.load_leaderboard(year) {
+        Ok(board) => board,
+        Err(error) => return page.flash(Flash::error(format!("Unable to read the leaderboard: {}", error))),
+    };
+    let can_fetch = aoc_service.client.settings.leaderboard_id.is_some();
+
+    page.body(html! {
+        div class="leaderboard-import" {
+            @if can_fetch {
+                form method="post" action=(format!("{}/refresh", leaderboard_path(year))) {
+                    button type="submit" { "Fetch from AoC" }
+                }
+            }
+            details {
+                summary { "Import json" }
+                form method="post" action=(format!("{}/import", leaderboard_path(year))) {
+                    p { "Paste the json from the leaderboard's API link." }
+                    textarea name="json" rows="8" cols="80" { }
+                    br;
+                    button type="submit" { "Import" }
+                }
+            }
+        }
+        @if let Some(board) = &board {
+            @let days = board.days();
+            @let last_day = days.last().copied().unwrap_or(0).min(DAYS_PER_YEAR);
+            @let unimplemented: Vec<u32> = days
+                .iter()
+                .copied()
+                .filter(|day| (1..=PARTS_PER_DAY).any(|part| !aoc_service.problem_answers.contains_key(&AocProblem { year, day: *day, part })))
+                .collect();
+
+            table class="leaderboard" {
+                tr {
+                    th { "#" } th { "Member" } th { "Score" } th { "Stars" }
+                    @for day in 1..=last_day {
+                        th class="star" { (day) }
+                    }
+                }
+                @for (rank, member) in board.ranked().iter().enumerate() {
+                    @let position = rank + 1;
+                    tr {
+                        td { (position) }
+                        td { (member.display_name()) }
+                        td { (member.local_score) }
+                        td { (member.stars) }
+                        @for day in 1..=last_day {
+                            (star_cell(year, day, member))
+                        }
+                    }
+                }
+            }
+            @if !unimplemented.is_empty() {
+                p {
+                    "Solved on the team but missing a solver here: "
+                    @for (n, day) in unimplemented.iter().enumerate() {
+                        @if n > 0 { ", " }
+                        a href=(day_path(year, *day)) { "Day " (day) }
+                    }
+                }
+            }
+            h2 { "Days" }
+            @for day in days.iter().rev() {
+                (day_table(&aoc_service, board, year, *day))
+            }
+        }
+        @else {
+            p { "No leaderboard has been imported for " (year) " yet." }
+        }
+    })
+}
+
+#[post("/aoc/{year}/leaderboard/refresh")]
+pub async fn refresh_leaderboard(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocYearRequest>) -> AwResult<HttpResponse> {
+    let year = request.year;
+    let fetched = web::block(move || aoc_service.fetch_leaderboard(year).map(|b| b.members.len()).map_err(|e| e.to_string())).await?;
+
+    Ok(match fetched {
+        Ok(members) => Flash::info(format!("Fetched the leaderboard, {} members.", members)).redirect(&leaderboard_path(year)),
+        Err(error) => Flash::error(format!("Unable to fetch the leaderboard: {}", error)).redirect(&leaderboard_path(year)),
+    })
+}
+
+/// The paste route, with room for a large leaderboard and form errors shown on the page.
+pub fn paste_leaderboard_resource() -> Resource {
+    let form_config = web::FormConfig::default()
+        .limit(PASTE_LIMIT)
+        .error_handler(|error, req| {
+            let back = match req.match_info().query("year").parse() {
+                Ok(year) => leaderboard_path(year),
+                Err(_) => String::from("/aoc"),
+            };
+            let response = Flash::error(format!("Unable to read the pasted leaderboard: {}", error)).redirect(&back);
+            InternalError::from_response(error, response).into()
+        });
+
+    web::resource("/aoc/{year}/leaderboard/import")
+        .app_data(form_config)
+        .route(web::post().to(paste_leaderboard))
+}
+
+async fn paste_leaderboard(
+    aoc_service: web::Data<Arc<AocService>>,
+    request: web::Path<AocYearRequest>,
+    form: web::Form<AocLeaderboardForm>) -> HttpResponse
+{
+    let year = request.year;
+    match aoc_service.import_leaderboard(year, &form.json) {
+        Ok(board) => Flash::info(format!("Imported the leaderboard, {} members.", board.members.len())).redirect(&leaderboard_path(year)),
+        Err(error) => Flash::error(format!("Unable to import the leaderboard: {}", error)).redirect(&leaderboard_path(year)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, App};
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::client::{AocClient, AocClientSettings};
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    async fn paste(body: String) -> (StatusCode, String, String) {
+        let directory = tempfile::tempdir().unwrap();
+        let mut aoc_service = AocService::new(AocInputSources::default(), String::from("www/aoc_problems"));
+        aoc_service.client = AocClient::new(AocClientSettings {
+            leaderboard_directory: directory.path().to_string_lossy().into_owned(),
+            ..AocClientSettings::default()
+        });
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(Arc::new(aoc_service)))
+            .service(paste_leaderboard_resource())).await;
+
+        let request = test::TestRequest::post()
+            .uri("/aoc/2022/leaderboard/import")
+            .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .set_payload(body)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+        (response.status(), header(header::LOCATION), header(header::SET_COOKIE))
+    }
+
+    fn form(json: &str) -> String {
+        let mut body = String::from("json=");
+        for byte in json.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => body.push(byte as char),
+                _ => body.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        body
+    }
+
+    #[actix_web::test]
+    async fn imports_leaderboards_larger_than_the_default_form_limit() {
+        // Whitespace after the json pads it past 16KB.
+        let json = fs::read_to_string("fixtures/aoc/2022/leaderboard_1501234.json").unwrap() + &" ".repeat(20_000);
+        let (status, location, cookie) = paste(form(&json)).await;
+
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert_eq!(location, "/aoc/2022/leaderboard");
+        assert!(cookie.starts_with("flash=info%3AImported%20the%20leaderboard"), "{}", cookie);
+    }
+
+    #[actix_web::test]
+    async fn oversized_pastes_are_shown_as_errors() {
+        let (status, location, cookie) = paste(form(&" ".repeat(PASTE_LIMIT))).await;
+
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert_eq!(location, "/aoc/2022/leaderboard");
+        assert!(cookie.starts_with("flash=error%3AUnable%20to%20read%20the%20pasted%20leaderboard"), "{}", cookie);
+    }
+
+    #[actix_web::test]
+    async fn unreadable_forms_and_json_are_shown_as_errors() {
+        let (status, _, cookie) = paste(String::from("name=x")).await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert!(cookie.starts_with("flash=error%3AUnable%20to%20read%20the%20pasted%20leaderboard"), "{}", cookie);
+
+        let (status, _, cookie) = paste(form("{\"members\": ")).await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert!(cookie.starts_with("flash=error%3AUnable%20to%20import%20the%20leaderboard"), "{}", cookie);
+    }
+}
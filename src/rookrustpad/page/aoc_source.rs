@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use actix_web::{get, web};
+use maud::{html, Markup};
+
+use crate::rookrustpad::page::aoc::{answer_path, AocDayRequest};
+use crate::rookrustpad::page::highlight::highlight_rust;
+use crate::rookrustpad::page::layout::Page;
+use crate::rookrustpad::service::aoc::{AocImplementation, AocService, AocSolver};
+
+pub fn source_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}/source", year, day)
+}
+
+/// A solver's source file with its function picked out, or the command of an external solver.
+pub fn solver_source(solver: &AocSolver) -> Markup {
+    let span = solver.function_span();
+
+    html! {
+        @match &solver.implementation {
+            AocImplementation::Native { source: solver_file, .. } => {
+                details id="source" class="solver-source" open {
+                    summary {
+                        "Source: " code { (solver.name) } " in " code { (solver_file.repository_path()) }
+                        @if let Some(span) = span {
+                            " " a href=(format!("#L{}", span.start_line)) {
+                                "lines " (span.start_line) "-" (span.end_line)
+                            }
+                        }
+                    }
+                    (highlight_rust(solver_file.text, &span.map(|s| vec![(s.start_line, s.end_line)]).unwrap_or_default()))
+                }
+            },
+            AocImplementation::External(external) => {
+                div id="source" class="solver-source" {
+                    p {
+                        "External command: " code { (external.command_line()) }
+                    }
+                    ul {
+                        li { "Input: " (if external.stdin { "path and stdin" } else { "path" }) }
+                        li { "Timeout: " (external.timeout) " seconds" }
+                        @if let Some(working_directory) = &external.working_directory {
+                            li { "Working directory: " code { (working_directory) } }
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[get("/aoc/{year}/{day}/source")]
+pub async fn source(
+    aoc_service: web::Data<Arc<AocService>>,
+    request: web::Path<AocDayRequest>) -> Page
+{
+    let day_source = aoc_service.get_day_source(request.year, request.day);
+
+    Page::aoc(format!("Day {} Source", request.day), Some(request.year), Some(request.day))
+        .body(html! {
+            @if day_source.files.is_empty() {
+                p { "There are no solvers for this day." }
+            }
+            @for file in &day_source.files {
+                h2 { code { (file.path) } }
+                ul {
+                    @for solver in &file.solvers {
+                        li {
+                            a href=(format!("{}?variant={}", answer_path(day_source.year, day_source.day, solver.part, &None), solver.variant)) {
+                                "Part " (solver.part)
+                            }
+                            " " (solver.variant) ": " code { (solver.name) }
+                            @if let Some(span) = solver.span {
+                                " " a href=(format!("#L{}", span.start_line)) {
+                                    "lines " (span.start_line) "-" (span.end_line)
+                                }
+                            }
+                        }
+                    }
+                }
+                @let spans: Vec<(usize, usize)> = file.solvers
+                    .iter()
+                    .filter_map(|s| s.span)
+                    .map(|s| (s.start_line, s.end_line))
+                    .collect();
+                (highlight_rust(file.source, &spans))
+            }
+        })
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpResponse};
+use maud::{html, PreEscaped};
+
+use crate::rookrustpad::page::aoc::{answer_path, statement_url, AocDayRequest};
+use crate::rookrustpad::page::aoc_source::source_path;
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::status::PARTS_PER_DAY;
+use crate::rookrustpad::service::aoc::{AocProblem, AocService};
+
+pub fn statement_path(year: u32, day: u32) -> String {
+    format!("/aoc/{}/{}/statement", year, day)
+}
+
+/// The imported puzzle description with the day's answers beside it.
+#[get("/aoc/{year}/{day}/statement")]
+pub async fn statement(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocDayRequest>) -> Page {
+    let AocDayRequest { year, day } = request.into_inner();
+    let page = Page::aoc(format!("Day {}", day), Some(year), Some(day)).crumb("Statement", None);
+
+    let text = match aoc_service.load_statement_text(year, day) {
+        Ok(Some(text)) => text,
+        Ok(None) => return page.flash(Flash::error("There is no saved statement for this day.")),
+        Err(error) => return page.flash(Flash::error(format!("Unable to import the statement: {}", error))),
+    };
+
+    let page = Page::aoc(format!("Day {}: {}", day, text.title), Some(year), Some(day)).crumb("Statement", None);
+    let has_solver = (1..=PARTS_PER_DAY).any(|part| aoc_service.problem_answers.contains_key(&AocProblem { year, day, part }));
+
+    page.body(html! {
+        div class="statement-layout" {
+            div class="statement" {
+                @for part in &text.parts {
+                    article { (PreEscaped(part)) }
+                }
+            }
+            aside class="statement-side" {
+                @for part in 1..=PARTS_PER_DAY {
+                    @let implemented = aoc_service.problem_answers.contains_key(&AocProblem { year, day, part });
+                    h3 { "Part " (part) }
+                    @if implemented {
+                        p {
+                            a href=(answer_path(year, day, part, &None)) { "Answer" }
+                            @if let Some(run) = aoc_service.history.latest(year, day, part, &None, &None) {
+                                ": "
+                                @match &run.answer.result {
+                                    Ok(result) => code { (result) },
+                                    Err(_) => span class="missing" { "failed" },
+                                }
+                                " " small { (format!("{:.4}s", run.answer.execution_time)) }
+                            }
+                        }
+                    }
+                    @else {
+                        p class="missing" { "No solver" }
+                    }
+                }
+                @if has_solver {
+                    p { a href=(source_path(year, day)) { "Solver source" } }
+                }
+                p { a href=(statement_url(year, day)) { "Saved page" } }
+                form method="post" action=(format!("{}/import", statement_path(year, day))) {
+                    button type="submit" title="Read the saved page again" { "Re-import" }
+                }
+            }
+        }
+    })
+}
+
+/// Admin: imports the saved page again, for when the cleaning has changed.
+#[post("/aoc/{year}/{day}/statement/import")]
+pub async fn import_statement(aoc_service: web::Data<Arc<AocService>>, request: web::Path<AocDayRequest>) -> HttpResponse {
+    let AocDayRequest { year, day } = request.into_inner();
+    let flash = match aoc_service.import_statement(year, day) {
+        Ok(text) => Flash::info(format!("Imported {} parts of {}.", text.parts.len(), text.title)),
+        Err(error) => Flash::error(format!("Unable to import the statement: {}", error)),
+    };
+    flash.redirect(&statement_path(year, day))
+}
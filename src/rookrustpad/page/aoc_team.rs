@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use actix_web::{get, web, Result as AwResult};
+use maud::{html, Markup};
+
+use crate::rookrustpad::api::aoc_handler::run_team_answers;
+use crate::rookrustpad::page::aoc::{answer_path, day_path, user_path, AocDayRequest};
+use crate::rookrustpad::page::layout::Page;
+use crate::rookrustpad::service::aoc::status::PARTS_PER_DAY;
+use crate::rookrustpad::service::aoc::{AocAnswer, AocService, AocVerdict};
+use crate::rookrustpad::service::solver_pool::SolverPool;
+
+fn team_cell(aoc_result: Option<&AocAnswer>) -> Markup {
+    let Some(aoc_result) = aoc_result else {
+        return html! { td class="cell empty" { "no input" } };
+    };
+
+    let verdict = aoc_result.verdict();
+    html! {
+        td class=(format!("cell {:?}", verdict)) {
+            a href=(user_path(answer_path(aoc_result.year, aoc_result.day, aoc_result.part, &None), &aoc_result.user)) {
+                @match &aoc_result.result {
+                    Ok(result) => code { (result) },
+                    Err(error) => span { (error) },
+                }
+            }
+            br { }
+            (format!("{:?}", verdict))
+            @if verdict == AocVerdict::Incorrect {
+                @if let Some(expected) = &aoc_result.expected {
+                    ", expected " code { (expected) }
+                }
+            }
+            br { }
+            small { (format!("{:.4}s", aoc_result.execution_time)) }
+        }
+    }
+}
+
+/// Runs each part on everyone's own input, to catch solvers that only work on one of them.
+#[get("/aoc/{year}/{day}/team")]
+pub async fn team_page(
+    aoc_service: web::Data<Arc<AocService>>,
+    solver_pool: web::Data<Arc<SolverPool>>,
+    request: web::Path<AocDayRequest>) -> AwResult<Page>
+{
+    let AocDayRequest { year, day } = request.into_inner();
+    let page = Page::aoc(format!("Day {} - Team", day), Some(year), Some(day)).crumb("Team", None);
+
+    let users = aoc_service.list_users()?;
+    let answers = run_team_answers(&aoc_service, &solver_pool, year, day).await?;
+    let parts: Vec<u8> = (1..=PARTS_PER_DAY).collect();
+
+    let answer_for = |user: &String, part: u8| {
+        answers.iter().find(|a| a.user.as_ref() == Some(user) && a.part == part)
+    };
+
+    Ok(page.body(html! {
+        @if users.is_empty() {
+            p { "Nobody has inputs of their own yet, they go in the input directory under " code { "<user>/<year>" } "." }
+        }
+        @else {
+            table class="day-matrix" {
+                tr {
+                    th { "User" }
+                    @for part in &parts {
+                        th { "Part " (part) }
+                    }
+                }
+                @for user in &users {
+                    tr {
+                        th { a href=(user_path(day_path(year, day), &Some(user.clone()))) { (user) } }
+                        @for part in &parts {
+                            (team_cell(answer_for(user, *part)))
+                        }
+                    }
+                }
+                tr {
+                    th { "Works for" }
+                    @for part in &parts {
+                        @let ran: Vec<&AocAnswer> = answers.iter().filter(|a| a.part == *part).collect();
+                        @let working = ran.iter().filter(|a| !matches!(a.verdict(), AocVerdict::Failed | AocVerdict::Incorrect)).count();
+                        td class=(if working < ran.len() { "cell Failed" } else { "cell" }) {
+                            (working) " of " (ran.len())
+                        }
+                    }
+                }
+            }
+        }
+    }))
+}
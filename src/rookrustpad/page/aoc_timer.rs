@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use maud::{html, Markup};
+use serde::Deserialize;
+
+use crate::rookrustpad::api::aoc_handler::request_user;
+use crate::rookrustpad::page::aoc::{answer_path, day_path, format_duration, format_time, user_path};
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::timer::{AocTimer, AocTimerAction, AocTimerState};
+use crate::rookrustpad::service::aoc::AocService;
+
+pub const TIMERS_PATH: &str = "/aoc/timers";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocTimerForm {
+    action: AocTimerAction,
+    user: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AocTimersQuery {
+    user: Option<String>,
+}
+
+pub fn timer_path(year: u32, day: u32, part: u8) -> String {
+    format!("/aoc/{}/{}/{}/timer", year, day, part)
+}
+
+/// Solver seconds with a unit that keeps a few digits, like 1.25ms or 3.4s.
+fn format_runtime(seconds: f64) -> String {
+    if seconds >= 1.0 {
+        format!("{:.2}s", seconds)
+    }
+    else if seconds >= 0.001 {
+        format!("{:.2}ms", seconds * 1e3)
+    }
+    else {
+        format!("{:.0}µs", seconds * 1e6)
+    }
+}
+
+/// The time on a timer, ticked along by timer.js while it runs.
+fn timer_clock(timer: &AocTimer) -> Markup {
+    html! {
+        span class="timer-clock"
+            data-elapsed=(timer.elapsed)
+            data-running-since=[timer.running_since] {
+            (format_duration(timer.seconds()))
+        }
+    }
+}
+
+fn timer_button(action: &str, label: &str) -> Markup {
+    html! {
+        button type="submit" name="action" value=(action) { (label) }
+    }
+}
+
+/// A part's timer with buttons to start, pause, stop or reset it.
+pub fn timer_controls(year: u32, day: u32, part: u8, user: &Option<String>, timer: Option<&AocTimer>) -> Markup {
+    html! {
+        form class="timer" method="post" action=(timer_path(year, day, part)) {
+            @if let Some(user) = user {
+                input type="hidden" name="user" value=(user);
+            }
+            "Part " (part) ": "
+            @match timer {
+                None => {
+                    (timer_button("start", "Start timer"))
+                },
+                Some(timer) => {
+                    (timer_clock(timer)) " "
+                    @if let Some(solve) = &timer.solve {
+                        span class="solved" title=(format!("{} from a {}", solve.answer, solve.source)) {
+                            "solved " (format_time(solve.solved_at))
+                        }
+                        " "
+                    }
+                    @else {
+                        span class="timer-state" { (format!("{:?}", timer.state).to_lowercase()) } " "
+                        @if timer.state == AocTimerState::Running {
+                            (timer_button("pause", "Pause")) " "
+                            (timer_button("stop", "Stop")) " "
+                        }
+                        @else {
+                            (timer_button("start", "Resume")) " "
+                        }
+                    }
+                    (timer_button("reset", "Reset"))
+                },
+            }
+        }
+    }
+}
+
+#[post("/aoc/{year}/{day}/{part}/timer")]
+pub async fn update_timer(
+    req: HttpRequest,
+    aoc_service: web::Data<Arc<AocService>>,
+    request: web::Path<(u32, u32, u8)>,
+    form: web::Form<AocTimerForm>) -> HttpResponse
+{
+    let (year, day, part) = request.into_inner();
+    let AocTimerForm { action, user } = form.into_inner();
+    let user = request_user(&req, user);
+    let back = user_path(day_path(year, day), &user);
+
+    let verb = match action {
+        AocTimerAction::Start => "start",
+        AocTimerAction::Pause => "pause",
+        AocTimerAction::Stop => "stop",
+        AocTimerAction::Reset => "reset",
+    };
+    match aoc_service.update_timer(year, day, part, &user, action) {
+        Ok(Some(timer)) => Flash::info(format!("Part {} timer: {:?}, {} so far.", part, timer.state, format_duration(timer.seconds()))).redirect(&back),
+        Ok(None) => Flash::info(format!("Reset the part {} timer.", part)).redirect(&back),
+        Err(error) => Flash::error(format!("Unable to {} the timer: {}", verb, error)).redirect(&back),
+    }
+}
+
+/// Solve times with the averages for each year, next to how long the solvers take.
+#[get("/aoc/timers")]
+pub async fn timers_page(req: HttpRequest, aoc_service: web::Data<Arc<AocService>>, query: web::Query<AocTimersQuery>) -> Page {
+    let user = request_user(&req, query.into_inner().user);
+    let page = Page::aoc("Solve times", None, None);
+
+    let timers = match aoc_service.list_timers(&user) {
+        Ok(timers) => timers,
+        Err(error) => return page.flash(Flash::error(format!("Unable to read the timers: {}", error))),
+    };
+
+    let mut years: BTreeMap<u32, Vec<&AocTimer>> = BTreeMap::new();
+    for timer in &timers {
+        years.entry(timer.year).or_default().push(timer);
+    }
+
+    page.body(html! {
+        @if let Some(user) = &user {
+            p { "Timers of " b { (user) } ". " a href=(TIMERS_PATH) { "Everyone's" } }
+        }
+        @if years.is_empty() {
+            p { "Nothing has been timed yet. Start a timer for a part on its day page." }
+        }
+        @for (year, timers) in years.iter().rev() {
+            @let solved: Vec<i64> = timers.iter().filter(|t| t.solve.is_some()).map(|t| t.seconds()).collect();
+            h2 { (year) }
+            p {
+                (solved.len()) " of " (timers.len()) " timed parts solved"
+                @if !solved.is_empty() {
+                    @let average = solved.iter().sum::<i64>() / solved.len() as i64;
+                    @let total: i64 = solved.iter().sum();
+                    ", " (format_duration(average)) " on average and " (format_duration(total)) " in all"
+                }
+                "."
+            }
+            table class="timers" {
+                tr {
+                    th { "Day" } th { "Part" } th { "User" } th { "Time spent" } th { "Started" } th { "Solved" }
+                    th { "Solver runtime" } th { "Spent / runtime" }
+                }
+                @for timer in timers {
+                    @let runtime = aoc_service.history
+                        .latest(timer.year, timer.day, timer.part, &timer.user, &None)
+                        .map(|run| run.answer.execution_time)
+                        .or(timer.solve.as_ref().and_then(|s| s.execution_time));
+                    tr {
+                        td { a href=(user_path(day_path(timer.year, timer.day), &timer.user)) { (timer.day) } }
+                        td { (timer.part) }
+                        td {
+                            @match &timer.user {
+                                Some(name) => a href=(user_path(TIMERS_PATH.to_string(), &timer.user)) { (name) },
+                                None => span class="timer-state" { "(shared)" },
+                            }
+                        }
+                        td { (timer_clock(timer)) }
+                        td { (format_time(timer.started_at)) }
+                        td {
+                            @match &timer.solve {
+                                Some(solve) => span title=(format!("{} from a {}", solve.answer, solve.source)) { (format_time(solve.solved_at)) },
+                                None => span class="timer-state" { (format!("{:?}", timer.state).to_lowercase()) },
+                            }
+                        }
+                        td {
+                            @match runtime {
+                                Some(runtime) => a href=(user_path(answer_path(timer.year, timer.day, timer.part, &None), &timer.user)) {
+                                    (format_runtime(runtime))
+                                },
+                                None => span class="timer-state" { "not run" },
+                            }
+                        }
+                        td {
+                            @if let Some(runtime) = runtime.filter(|r| *r > 0.0 && timer.solve.is_some()) {
+                                @let ratio = timer.elapsed as f64 / 1000.0 / runtime;
+                                (format!("{:.0}×", ratio))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }).script("/assets/timer.js")
+}
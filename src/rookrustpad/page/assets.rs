@@ -0,0 +1,22 @@
+use actix_web::http::header;
+use actix_web::{get, web, HttpResponse};
+
+/// Files bundled into the binary and served under /assets.
+const ASSETS: &[(&str, &str, &str)] = &[
+    ("site.css", "text/css; charset=utf-8", include_str!("assets/site.css")),
+    ("answer.js", "text/javascript; charset=utf-8", include_str!("assets/answer.js")),
+    ("timer.js", "text/javascript; charset=utf-8", include_str!("assets/timer.js")),
+];
+
+#[get("/assets/{name}")]
+pub async fn asset(name: web::Path<String>) -> HttpResponse {
+    match ASSETS.iter().find(|(asset_name, _, _)| *asset_name == name.as_str()) {
+        Some((_, content_type, body)) =>
+            HttpResponse::Ok()
+                .content_type(*content_type)
+                .insert_header((header::CACHE_CONTROL, "public, max-age=3600"))
+                .body(*body),
+        None =>
+            HttpResponse::NotFound().finish(),
+    }
+}
@@ -0,0 +1,304 @@
+// Runs answers from the answer page through the JSON API and updates it in place.
+(function () {
+    "use strict";
+
+    const runner = document.getElementById("runner");
+    if (!runner) {
+        return;
+    }
+
+    const year = runner.dataset.year;
+    const day = runner.dataset.day;
+    const part = runner.dataset.part;
+    const user = runner.dataset.user;
+
+    const datasetSelect = document.getElementById("dataset");
+    const variantSelect = document.getElementById("variant");
+    const statusEl = document.getElementById("status");
+    const answerEl = document.getElementById("answer");
+    const submitForm = document.getElementById("submit");
+    const submitAnswerEl = document.getElementById("submit-answer");
+    const verdictEl = document.getElementById("verdict");
+    const expectedEl = document.getElementById("expected");
+    const timeEl = document.getElementById("execution-time");
+    const benchmarkEl = document.getElementById("benchmark-result");
+    const logEl = document.getElementById("log");
+    const artifactsEl = document.getElementById("artifacts");
+    const comparisonEl = document.getElementById("comparison");
+    const paramsForm = document.getElementById("params");
+    const iterationsEl = document.getElementById("iterations");
+    const buttons = runner.querySelectorAll("button");
+
+    function answerPath() {
+        const dataset = datasetSelect.value;
+        let path = "/aoc/" + year + "/" + day + "/" + part + "/answer";
+        if (dataset) {
+            path += "/" + encodeURIComponent(dataset);
+        }
+        return path;
+    }
+
+    // The answer page, keeping the user and the variant when one is chosen.
+    function pagePath() {
+        return answerPath() + "?" + withUser(new URLSearchParams(variantSelect ? { variant: variantSelect.value } : {}));
+    }
+
+    // Runs on the same user's inputs as the page.
+    function withUser(params) {
+        if (user) {
+            params.set("user", user);
+        }
+        return params;
+    }
+
+    // Adds the solver parameters from the parameter form, when the solver has any.
+    function addParams(params) {
+        if (!paramsForm) {
+            return params;
+        }
+        new FormData(paramsForm).forEach(function (value, name) {
+            if (name !== "variant" && name !== "user") {
+                params.set(name, value);
+            }
+        });
+        return params;
+    }
+
+    function verdict(answer) {
+        if (answer.result.Err !== undefined) {
+            return "Failed";
+        }
+        if (answer.expected === null || answer.expected === undefined) {
+            return "Unverified";
+        }
+        return answer.result.Ok.trim() === answer.expected.trim() ? "Correct" : "Incorrect";
+    }
+
+    const SVG_NS = "http://www.w3.org/2000/svg";
+    const CHART_WIDTH = 480;
+    const CHART_HEIGHT = 200;
+    const CHART_PADDING = 8;
+
+    function element(name, text) {
+        const el = document.createElement(name);
+        if (text !== undefined) {
+            el.textContent = text;
+        }
+        return el;
+    }
+
+    // The same chart as series_chart in aoc_artifact.rs.
+    function seriesChart(points) {
+        const chart = document.createDocumentFragment();
+        if (points.length === 0) {
+            const empty = element("p", "No points.");
+            empty.className = "series-range";
+            chart.appendChild(empty);
+            return chart;
+        }
+        const xs = points.map(function (p) { return p[0]; });
+        const ys = points.map(function (p) { return p[1]; });
+        const minX = Math.min.apply(null, xs), maxX = Math.max.apply(null, xs);
+        const minY = Math.min.apply(null, ys), maxY = Math.max.apply(null, ys);
+        const scale = function (v, lo, hi, size) {
+            return hi > lo ? (v - lo) / (hi - lo) * (size - 2 * CHART_PADDING) : 0;
+        };
+
+        const svg = document.createElementNS(SVG_NS, "svg");
+        svg.setAttribute("class", "series-chart");
+        svg.setAttribute("viewBox", "0 0 " + CHART_WIDTH + " " + CHART_HEIGHT);
+        svg.setAttribute("width", CHART_WIDTH);
+        svg.setAttribute("height", CHART_HEIGHT);
+        const line = document.createElementNS(SVG_NS, "polyline");
+        line.setAttribute("points", points.map(function (p) {
+            return (CHART_PADDING + scale(p[0], minX, maxX, CHART_WIDTH)).toFixed(1) + "," +
+                (CHART_HEIGHT - CHART_PADDING - scale(p[1], minY, maxY, CHART_HEIGHT)).toFixed(1);
+        }).join(" "));
+        line.setAttribute("fill", "none");
+        line.setAttribute("stroke", "currentColor");
+        line.setAttribute("stroke-width", "2");
+        svg.appendChild(line);
+        chart.appendChild(svg);
+
+        const range = element("div", "x " + minX + " to " + maxX + ", y " + minY + " to " + maxY);
+        range.className = "series-range";
+        chart.appendChild(range);
+        return chart;
+    }
+
+    function showArtifacts(artifacts) {
+        artifactsEl.replaceChildren();
+        (artifacts || []).forEach(function (artifact) {
+            const section = element("section");
+            section.className = "artifact";
+            section.appendChild(element("h3", artifact.title));
+            if (artifact.kind === "table") {
+                const table = element("table");
+                artifact.rows.forEach(function (row) {
+                    const tr = element("tr");
+                    tr.appendChild(element("th", row[0]));
+                    tr.appendChild(element("td", row[1]));
+                    table.appendChild(tr);
+                });
+                section.appendChild(table);
+            } else if (artifact.kind === "grid") {
+                const grid = element("pre", artifact.rows.join("\n"));
+                grid.className = "grid";
+                section.appendChild(grid);
+            } else if (artifact.kind === "series") {
+                section.appendChild(seriesChart(artifact.points));
+            } else if (artifact.kind === "svg") {
+                const svg = element("div");
+                svg.className = "svg";
+                svg.innerHTML = artifact.svg;
+                section.appendChild(svg);
+            }
+            artifactsEl.appendChild(section);
+        });
+    }
+
+    function showAnswer(answer) {
+        if (answer.result.Err !== undefined) {
+            answerEl.textContent = answer.result.Err;
+            answerEl.className = "error";
+        } else {
+            answerEl.textContent = answer.result.Ok;
+            answerEl.className = "";
+        }
+        // Only the main input's answer can be submitted.
+        submitForm.hidden = answer.result.Err !== undefined || answer.dataset !== null || !!answer.user;
+        submitAnswerEl.value = answer.result.Ok || "";
+        verdictEl.textContent = verdict(answer);
+        expectedEl.textContent = answer.expected ? " (expected " + answer.expected + ")" : "";
+        timeEl.textContent = answer.execution_time;
+        logEl.value = answer.log;
+        if (paramsForm) {
+            Object.keys(answer.params).forEach(function (name) {
+                if (paramsForm.elements[name]) {
+                    paramsForm.elements[name].value = String(answer.params[name]);
+                }
+            });
+        }
+        showArtifacts(answer.artifacts);
+    }
+
+    async function fetchJson(url) {
+        const response = await fetch(url, { headers: { "Accept": "application/json" } });
+        if (response.status === 503) {
+            const retry = response.headers.get("Retry-After");
+            throw new Error("The solvers are busy, try again in " + (retry || "a few") + " seconds.");
+        }
+        if (!response.ok) {
+            throw new Error(response.status + " " + (await response.text()));
+        }
+        return response.json();
+    }
+
+    async function busy(message, work) {
+        buttons.forEach(function (b) { b.disabled = true; });
+        statusEl.textContent = message;
+        statusEl.className = "runner-status";
+        try {
+            await work();
+            statusEl.textContent = "";
+        } catch (error) {
+            statusEl.textContent = error.message;
+            statusEl.className = "runner-status error";
+        } finally {
+            buttons.forEach(function (b) { b.disabled = false; });
+        }
+    }
+
+    function run(cached) {
+        return busy("Running...", async function () {
+            const params = new URLSearchParams({ format: "json" });
+            if (cached) {
+                params.set("cached", "true");
+            }
+            if (variantSelect) {
+                params.set("variant", variantSelect.value);
+            }
+            showAnswer(await fetchJson(answerPath() + "?" + addParams(withUser(params))));
+        });
+    }
+
+    function benchmark() {
+        return busy("Benchmarking...", async function () {
+            const params = new URLSearchParams({ iterations: iterationsEl.value || "10" });
+            if (datasetSelect.value) {
+                params.set("dataset", datasetSelect.value);
+            }
+            if (variantSelect) {
+                params.set("variant", variantSelect.value);
+            }
+            const result = await fetchJson("/api/aoc/benchmark/" + year + "/" + day + "/" + part + "?" + addParams(withUser(params)));
+            showAnswer(result.answer);
+            benchmarkEl.textContent =
+                result.iterations + " runs: min " + result.min_time.toFixed(6) +
+                "s, median " + result.median_time.toFixed(6) +
+                "s, mean " + result.mean_time.toFixed(6) +
+                "s, max " + result.max_time.toFixed(6) + "s";
+        });
+    }
+
+    function compare() {
+        return busy("Comparing variants...", async function () {
+            const params = new URLSearchParams({ iterations: iterationsEl.value || "10" });
+            if (datasetSelect.value) {
+                params.set("dataset", datasetSelect.value);
+            }
+            const comparison = await fetchJson("/api/aoc/compare/" + year + "/" + day + "/" + part + "?" + addParams(withUser(params)));
+
+            comparisonEl.replaceChildren();
+            const summary = element("p", comparison.agree ? "All variants agree." : "The variants do not agree.");
+            summary.className = comparison.agree ? "ok" : "missing";
+            comparisonEl.appendChild(summary);
+
+            const table = element("table");
+            const header = element("tr");
+            ["Variant", "Answer", "Runs", "Min", "Median", "Mean", "Max"].forEach(function (h) {
+                header.appendChild(element("th", h));
+            });
+            table.appendChild(header);
+            comparison.benchmarks.forEach(function (b) {
+                const row = element("tr");
+                const answer = b.answer.result.Err !== undefined ? "Error: " + b.answer.result.Err : b.answer.result.Ok;
+                [b.variant, answer, b.iterations].concat(
+                    [b.min_time, b.median_time, b.mean_time, b.max_time].map(function (t) { return t.toFixed(6) + "s"; })
+                ).forEach(function (cell) {
+                    row.appendChild(element("td", cell));
+                });
+                table.appendChild(row);
+            });
+            comparisonEl.appendChild(table);
+        });
+    }
+
+    datasetSelect.addEventListener("change", function () {
+        history.replaceState(null, "", pagePath());
+        benchmarkEl.textContent = "";
+        comparisonEl.replaceChildren();
+        run(true);
+    });
+
+    // The source shown belongs to the variant, so load the page for the new one.
+    if (variantSelect) {
+        variantSelect.addEventListener("change", function () {
+            window.location.assign(pagePath() + "&cached=true");
+        });
+        document.getElementById("compare").addEventListener("click", compare);
+    }
+
+    document.getElementById("run").addEventListener("click", function () { run(true); });
+    document.getElementById("rerun").addEventListener("click", function () { run(false); });
+    document.getElementById("benchmark").addEventListener("click", benchmark);
+
+    document.getElementById("copy").addEventListener("click", async function () {
+        try {
+            await navigator.clipboard.writeText(answerEl.textContent);
+            statusEl.textContent = "Copied.";
+        } catch (error) {
+            statusEl.textContent = "Unable to copy: " + error.message;
+        }
+    });
+})();
@@ -0,0 +1,37 @@
+// Keeps the clocks of running timers ticking between page loads.
+(function () {
+    "use strict";
+
+    const clocks = document.querySelectorAll(".timer-clock[data-running-since]");
+    if (clocks.length === 0) {
+        return;
+    }
+
+    // Same format as format_duration on the server.
+    function formatDuration(seconds) {
+        const pad = (n) => String(n).padStart(2, "0");
+        const days = Math.floor(seconds / 86400);
+        const hours = Math.floor(seconds / 3600) % 24;
+        const minutes = Math.floor(seconds / 60) % 60;
+        const secs = seconds % 60;
+
+        if (days > 0) {
+            return days + "d " + hours + "h " + pad(minutes) + "m";
+        }
+        if (hours > 0) {
+            return hours + "h " + pad(minutes) + "m " + pad(secs) + "s";
+        }
+        return minutes + "m " + pad(secs) + "s";
+    }
+
+    function tick() {
+        const now = Date.now();
+        clocks.forEach((clock) => {
+            const elapsed = Number(clock.dataset.elapsed) + Math.max(0, now - Number(clock.dataset.runningSince));
+            clock.textContent = formatDuration(Math.floor(elapsed / 1000));
+        });
+    }
+
+    tick();
+    setInterval(tick, 1000);
+})();
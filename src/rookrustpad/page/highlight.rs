@@ -0,0 +1,101 @@
+use lazy_regex::regex;
+use maud::{html, Markup};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while",
+];
+
+/// Splits rust source into (css class, text) tokens, text without a class is plain.
+fn tokenize(text: &str) -> Vec<(Option<&'static str>, &str)> {
+    let token_re = regex!(
+        r##"(?P<comment>//[^\n]*|/\*[\s\S]*?\*/)|(?P<string>b?r#"[\s\S]*?"#|b?r"[^"]*"|b?"(?:\\[\s\S]|[^"\\])*")|(?P<char>b?'(?:\\.|[^'\\])')|(?P<lifetime>'[A-Za-z_]\w*)|(?P<attribute>#!?\[[^\]]*\])|(?P<number>\b\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?(?:[iuf](?:8|16|32|64|128|size))?\b)|(?P<macro>\b[A-Za-z_]\w*!)|(?P<ident>\b[A-Za-z_]\w*\b)"##
+    );
+
+    let mut tokens = vec![];
+    let mut last = 0;
+
+    for caps in token_re.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        if whole.start() > last {
+            tokens.push((None, &text[last..whole.start()]));
+        }
+
+        let class = if caps.name("comment").is_some() {
+            Some("tok-comment")
+        }
+        else if caps.name("string").is_some() || caps.name("char").is_some() {
+            Some("tok-string")
+        }
+        else if caps.name("lifetime").is_some() {
+            Some("tok-lifetime")
+        }
+        else if caps.name("attribute").is_some() {
+            Some("tok-attribute")
+        }
+        else if caps.name("number").is_some() {
+            Some("tok-number")
+        }
+        else if caps.name("macro").is_some() {
+            Some("tok-macro")
+        }
+        else if KEYWORDS.contains(&whole.as_str()) {
+            Some("tok-keyword")
+        }
+        else if whole.as_str().starts_with(|c: char| c.is_ascii_uppercase()) {
+            Some("tok-type")
+        }
+        else {
+            None
+        };
+
+        tokens.push((class, whole.as_str()));
+        last = whole.end();
+    }
+
+    if last < text.len() {
+        tokens.push((None, &text[last..]));
+    }
+
+    tokens
+}
+
+/// Renders highlighted rust source with line numbers. Lines in the spans
+/// (counting from 1, inclusive) are picked out.
+pub fn highlight_rust(text: &str, spans: &[(usize, usize)]) -> Markup {
+    // Tokens such as block comments can cover several lines, so split them up.
+    let mut lines: Vec<Vec<(Option<&'static str>, &str)>> = vec![vec![]];
+    for (class, token) in tokenize(text) {
+        let mut pieces = token.split('\n');
+        if let Some(first) = pieces.next() {
+            lines.last_mut().unwrap().push((class, first));
+        }
+        for piece in pieces {
+            lines.push(vec![(class, piece)]);
+        }
+    }
+
+    let in_span = |line: usize| spans.iter().any(|(start, end)| line >= *start && line <= *end);
+
+    html! {
+        pre class="source" {
+            code {
+                @for (n, tokens) in lines.iter().enumerate() {
+                    @let line = n + 1;
+                    span id=(format!("L{}", line)) class=(if in_span(line) { "line highlight" } else { "line" }) {
+                        span class="line-number" { (line) }
+                        @for (class, token) in tokens {
+                            @match class {
+                                Some(class) => span class=(class) { (token) },
+                                None => (token),
+                            }
+                        }
+                    }
+                    "\n"
+                }
+            }
+        }
+    }
+}
@@ -1,26 +1,20 @@
-use actix_web::{get, Result as AwResult};
-use maud::{html, Markup};
+use actix_web::get;
+use maud::html;
+
+use crate::rookrustpad::page::layout::Page;
 
 #[get("/")]
-pub async fn index() -> AwResult<Markup> {
-    Ok(html! {
-        html {
-            head {
-                title { "Rook Rustpad" }
-            }
-            body {
-                h1 { "Rook Rustpad" }
-                p {
-                    "This is a place for me to practice some rust code."
-                }
-                ul {
-                    li {
-                        a href="/aoc" {
-                            "Advent of Code"
-                        }
-                    }
+pub async fn index() -> Page {
+    Page::new("Rook Rustpad").body(html! {
+        p {
+            "This is a place for me to practice some rust code."
+        }
+        ul {
+            li {
+                a href="/aoc" {
+                    "Advent of Code"
                 }
             }
         }
     })
-}
\ No newline at end of file
+}
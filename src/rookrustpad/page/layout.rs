@@ -0,0 +1,194 @@
+use actix_web::body::BoxBody;
+use actix_web::cookie::Cookie;
+use actix_web::http::header::{self, ContentType};
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use maud::{html, Markup, DOCTYPE};
+
+const SITE_NAME: &str = "Rook Rustpad";
+const FLASH_COOKIE: &str = "flash";
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum FlashLevel {
+    Info,
+    Error,
+}
+
+impl FlashLevel {
+    fn name(&self) -> &'static str {
+        match self {
+            FlashLevel::Info => "info",
+            FlashLevel::Error => "error",
+        }
+    }
+}
+
+/// A one time message shown on the next page that gets rendered.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Flash {
+    pub level: FlashLevel,
+    pub message: String,
+}
+
+impl Flash {
+    pub fn info(message: impl Into<String>) -> Flash {
+        Flash { level: FlashLevel::Info, message: message.into() }
+    }
+
+    pub fn error(message: impl Into<String>) -> Flash {
+        Flash { level: FlashLevel::Error, message: message.into() }
+    }
+
+    fn from_request(req: &HttpRequest) -> Option<Flash> {
+        let cookie = req.cookie(FLASH_COOKIE)?;
+        let (level, message) = cookie.value().split_once(':')?;
+        let level = match level {
+            "error" => FlashLevel::Error,
+            _ => FlashLevel::Info,
+        };
+        Some(Flash { level, message: message.to_string() })
+    }
+
+    /// Redirects to a page that will show this message.
+    pub fn redirect(self, location: &str) -> HttpResponse {
+        let cookie = Cookie::build(FLASH_COOKIE, format!("{}:{}", self.level.name(), self.message))
+            .path("/")
+            .finish();
+
+        HttpResponse::SeeOther()
+            .insert_header((header::LOCATION, location))
+            .insert_header((header::SET_COOKIE, cookie.encoded().to_string()))
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Crumb {
+    pub label: String,
+    pub href: Option<String>,
+}
+
+/// The shared frame for every html page: title, nav bar, breadcrumbs,
+/// flash message and footer. Pages only supply the body.
+#[derive(Debug, Clone)]
+pub struct Page {
+    title: String,
+    crumbs: Vec<Crumb>,
+    flashes: Vec<Flash>,
+    scripts: Vec<String>,
+    body: Markup,
+}
+
+impl Page {
+    pub fn new(title: impl Into<String>) -> Page {
+        Page {
+            title: title.into(),
+            crumbs: vec![Crumb { label: String::from("Home"), href: Some(String::from("/")) }],
+            flashes: vec![],
+            scripts: vec![],
+            body: html! {},
+        }
+    }
+
+    /// Starts a page under Home › AoC, adding the year and day when given.
+    pub fn aoc(title: impl Into<String>, year: Option<u32>, day: Option<u32>) -> Page {
+        let mut page = Page::new(title).crumb("AoC", Some("/aoc"));
+        if let Some(year) = year {
+            page = page.crumb(year.to_string(), Some(&format!("/aoc#year-{}", year)));
+            if let Some(day) = day {
+                page = page.crumb(format!("Day {}", day), Some(&format!("/aoc/{}/{}", year, day)));
+            }
+        }
+        page
+    }
+
+    pub fn crumb(mut self, label: impl Into<String>, href: Option<&str>) -> Page {
+        self.crumbs.push(Crumb { label: label.into(), href: href.map(String::from) });
+        self
+    }
+
+    /// Shows a message on this page rather than the next one.
+    pub fn flash(mut self, flash: Flash) -> Page {
+        self.flashes.push(flash);
+        self
+    }
+
+    pub fn script(mut self, src: impl Into<String>) -> Page {
+        self.scripts.push(src.into());
+        self
+    }
+
+    pub fn body(mut self, body: Markup) -> Page {
+        self.body = body;
+        self
+    }
+
+    pub fn render(&self) -> Markup {
+        html! {
+            (DOCTYPE)
+            html lang="en" {
+                head {
+                    meta charset="utf-8";
+                    meta name="viewport" content="width=device-width, initial-scale=1";
+                    title {
+                        @if self.title == SITE_NAME { (SITE_NAME) } @else { (self.title) " - " (SITE_NAME) }
+                    }
+                    link rel="stylesheet" href="/assets/site.css";
+                    @for script in &self.scripts {
+                        script src=(script) defer { }
+                    }
+                }
+                body {
+                    nav class="site-nav" {
+                        a class="brand" href="/" { (SITE_NAME) }
+                        a href="/aoc" { "Advent of Code" }
+                        form class="nav-search" action="/search" method="get" {
+                            input type="search" name="q" placeholder="Search" aria-label="Search";
+                        }
+                    }
+                    @if self.crumbs.len() > 1 {
+                        ol class="breadcrumbs" {
+                            @for crumb in &self.crumbs {
+                                li {
+                                    @match &crumb.href {
+                                        Some(href) => a href=(href) { (crumb.label) },
+                                        None => span { (crumb.label) },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    main {
+                        @for flash in &self.flashes {
+                            div class=(format!("flash {}", flash.level.name())) { (flash.message) }
+                        }
+                        h1 { (self.title) }
+                        (self.body)
+                    }
+                    footer class="site-footer" {
+                        (SITE_NAME) " · "
+                        a href="https://github.com/castle8080/rookrustpad" { "source" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Responder for Page {
+    type Body = BoxBody;
+
+    fn respond_to(mut self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let mut response = HttpResponse::Ok();
+        response.content_type(ContentType::html());
+
+        // The message is shown now, so the cookie goes away.
+        if let Some(flash) = Flash::from_request(req) {
+            self.flashes.insert(0, flash);
+            let mut removal = Cookie::build(FLASH_COOKIE, "").path("/").finish();
+            removal.make_removal();
+            response.insert_header((header::SET_COOKIE, removal.to_string()));
+        }
+
+        response.body(self.render().into_string())
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use actix_web::{get, post, web, HttpResponse, Result as AwResult};
+use maud::html;
+use serde::Deserialize;
+
+use crate::rookrustpad::page::aoc::day_path;
+use crate::rookrustpad::page::aoc_source::source_path;
+use crate::rookrustpad::page::aoc_statement::statement_path;
+use crate::rookrustpad::page::layout::{Flash, Page};
+use crate::rookrustpad::service::aoc::search::{AocSearchHit, AocSearchKind};
+use crate::rookrustpad::service::aoc::AocService;
+
+/// The most results shown for a query.
+const SEARCH_LIMIT: usize = 50;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchQuery {
+    q: Option<String>,
+}
+
+fn hit_path(hit: &AocSearchHit) -> String {
+    match hit.kind {
+        AocSearchKind::Statement => statement_path(hit.year, hit.day),
+        AocSearchKind::Source => format!("{}#L{}", source_path(hit.year, hit.day), hit.line),
+        AocSearchKind::Notes => day_path(hit.year, hit.day),
+    }
+}
+
+/// Searches the puzzle statements, solver sources and notes, "quoted phrases" are matched as written.
+#[get("/search")]
+pub async fn search(aoc_service: web::Data<Arc<AocService>>, query: web::Query<SearchQuery>) -> AwResult<Page> {
+    let q = query.into_inner().q.unwrap_or_default();
+    let page = Page::new("Search").crumb("Search", None);
+
+    // Building the index reads every statement and note.
+    let query_text = q.clone();
+    let hits = match web::block(move || aoc_service.search(&query_text, SEARCH_LIMIT).map_err(|e| e.to_string())).await? {
+        Ok(hits) => hits,
+        Err(error) => return Ok(page.flash(Flash::error(format!("Unable to search: {}", error)))),
+    };
+
+    Ok(page.body(html! {
+        form class="search-form" action="/search" method="get" {
+            input type="search" name="q" value=(q) placeholder="dijkstra, \"divisible by\"" autofocus;
+            " "
+            button type="submit" { "Search" }
+        }
+        form class="search-reindex" action="/search/reindex" method="post" {
+            button type="submit" title="Build the index again now, searches do when the files change" { "Reindex" }
+        }
+        @if !q.trim().is_empty() {
+            @if hits.is_empty() {
+                p { "Nothing matched." }
+            }
+            @else {
+                ol class="search-results" {
+                    @for hit in &hits {
+                        li {
+                            a href=(hit_path(hit)) { (hit.year) " " (hit.title) }
+                            " " small class="search-kind" {
+                                (format!("{:?}", hit.kind))
+                                @if hit.kind == AocSearchKind::Source { ", line " (hit.line) }
+                                " · " (hit.matches) (if hit.matches == 1 { " match" } else { " matches" })
+                            }
+                            p class="search-snippet" {
+                                @for part in &hit.snippet {
+                                    @if part.highlighted { mark { (part.text) } } @else { (part.text) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Builds the search index again right away.
+#[post("/search/reindex")]
+pub async fn reindex(aoc_service: web::Data<Arc<AocService>>) -> AwResult<HttpResponse> {
+    let indexed = web::block(move || aoc_service.reindex_search().map_err(|e| e.to_string())).await?;
+
+    Ok(match indexed {
+        Ok(count) => Flash::info(format!("Indexed {} documents.", count)).redirect("/search"),
+        Err(error) => Flash::error(format!("Unable to index: {}", error)).redirect("/search"),
+    })
+}
@@ -1 +1,2 @@
 pub mod aoc;
+pub mod solver_pool;
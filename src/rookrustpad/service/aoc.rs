@@ -1,23 +1,160 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fs::read_dir;
 use std::time::SystemTime;
 
 use lazy_regex::regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+use client::{AocClient, AocClientSettings};
+use external::AocExternalSolver;
+use history::{AocRunHistory, DEFAULT_HISTORY_DIRECTORY};
+use input_source::{AocInputSource, AocInputSources};
+use metadata::AocInputMetadata;
+use output::{AocArtifact, AocOutput};
+use params::{AocParam, AocParamInput, AocParams};
+use search::AocSearch;
+use source::{AocSource, AocSourceSpan};
+use statement::DEFAULT_IMPORTED_STATEMENT_DIRECTORY;
+use submission::AocSubmissionLog;
+use team::is_user_name;
+use timer::{AocTimers, DEFAULT_TIMER_FILE};
+
+/// Builds an AocSolver for year_module::day_module::function, embedding
+/// the day module's source file. The variant defaults to "default".
+macro_rules! aoc_solver {
+    ($year:ident, $day:ident, $function:ident) => {
+        aoc_solver!($year, $day, $function, "default")
+    };
+    ($year:ident, $day:ident, $function:ident, $variant:literal) => {
+        crate::rookrustpad::service::aoc::AocSolver {
+            name: String::from(concat!(stringify!($year), "::", stringify!($day), "::", stringify!($function))),
+            variant: String::from($variant),
+            params: &[],
+            implementation: crate::rookrustpad::service::aoc::AocImplementation::Native {
+                function: $day::$function,
+                source: crate::rookrustpad::service::aoc::source::AocSource {
+                    path: concat!(stringify!($year), "/", stringify!($day), ".rs"),
+                    text: include_str!(concat!(stringify!($year), "/", stringify!($day), ".rs")),
+                },
+            },
+        }
+    };
+}
+
+pub mod benchmark;
+pub mod client;
+pub mod compare;
+pub mod coverage;
+pub mod examples;
+pub mod external;
+pub mod history;
+pub mod input_source;
+pub mod leaderboard;
+pub mod metadata;
+pub mod mock;
+pub mod ocr;
+pub mod output;
+pub mod params;
+pub mod search;
+pub mod source;
+pub mod statement;
+pub mod status;
+pub mod submission;
+pub mod team;
+pub mod timer;
 pub mod y2022;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq)]
+pub struct AocAnswerRequest {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    /// Whose inputs to use, the shared ones when None.
+    #[serde(default)]
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+    pub variant: Option<String>,
+    /// Solver parameters, numbers and booleans are taken as written.
+    #[serde(default, deserialize_with = "deserialize_params")]
+    pub params: AocParamInput,
+}
+
+fn deserialize_params<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AocParamInput, D::Error> {
+    let values: BTreeMap<String, serde_json::Value> = BTreeMap::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AocAnswer {
     pub year: u32,
     pub day: u32,
     pub part: u8,
+    /// Whose inputs it ran on, None for the shared ones.
+    #[serde(default)]
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+    /// The variant that ran, None when there was no solver.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// The parameters the solver ran with, defaults included.
+    #[serde(default)]
+    pub params: AocParams,
     pub result: Result<String, String>,
+    #[serde(default)]
+    pub artifacts: Vec<AocArtifact>,
+    pub expected: Option<String>,
+    /// True when the parameters were the dataset's own, expected answers only hold then.
+    #[serde(default)]
+    pub default_params: bool,
     pub log: String,
     pub execution_time: f64,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AocVerdict {
+    Correct,
+    Incorrect,
+    Unverified,
+    Failed,
+}
+
+impl AocAnswer {
+    pub fn failed(request: &AocAnswerRequest, error: String) -> AocAnswer {
+        AocAnswer {
+            year: request.year,
+            day: request.day,
+            part: request.part,
+            user: request.user.clone(),
+            dataset: request.dataset.clone(),
+            variant: None,
+            params: AocParams::default(),
+            result: Err(error),
+            artifacts: vec![],
+            expected: None,
+            default_params: false,
+            log: String::new(),
+            execution_time: 0.0,
+        }
+    }
+
+    pub fn verdict(&self) -> AocVerdict {
+        match (&self.result, &self.expected) {
+            (Err(_), _) => AocVerdict::Failed,
+            (Ok(_), None) => AocVerdict::Unverified,
+            (Ok(result), Some(expected)) if result.trim() == expected.trim() => AocVerdict::Correct,
+            (Ok(_), Some(_)) => AocVerdict::Incorrect,
+        }
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Serialize, Deserialize)]
 pub struct AocProblem {
     pub year: u32,
     pub day: u32,
@@ -29,89 +166,263 @@ pub struct AocInput {
     pub year: u32,
     pub day: u32,
     pub part: u8,
+    /// The user the input belongs to, None for a shared input.
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+    /// Where the input is read from, a path or the archive and the file in it.
     pub input: String,
+    /// From the input's sidecar file, when it has one.
+    pub metadata: Option<AocInputMetadata>,
+}
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct AocStatement {
+    pub year: u32,
+    pub day: u32,
+    pub path: String,
 }
 
-type AocFunctionResult = Result<String, Box<dyn Error>>;
+/// An answer known to be right, from an input's sidecar.
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct AocExpectedAnswer {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub dataset: Option<String>,
+    pub answer: String,
+}
+
+type AocFunctionResult = Result<AocOutput, Box<dyn Error>>;
 
-pub type AocFunction= fn(input_path: String, log: &mut String) -> AocFunctionResult;
+pub type AocFunction= fn(input_path: String, params: &AocParams, log: &mut String) -> AocFunctionResult;
+
+#[derive(Debug, Clone)]
+pub enum AocImplementation {
+    /// A rust function built into the server, with the source of its module.
+    Native { function: AocFunction, source: AocSource },
+    /// A command from the settings, run for every answer.
+    External(AocExternalSolver),
+}
+
+#[derive(Debug, Clone)]
+pub struct AocSolver {
+    /// Module path of the function, for example y2022::day11::part2, or
+    /// the command line of an external solver.
+    pub name: String,
+    /// Tells implementations of the same problem apart, for example naive or vec_soa.
+    pub variant: String,
+    /// What the function accepts in its AocParams.
+    pub params: &'static [AocParam],
+    pub implementation: AocImplementation,
+}
+
+impl AocSolver {
+    pub fn external(external: AocExternalSolver) -> AocSolver {
+        AocSolver {
+            name: external.command_line(),
+            variant: external.variant.clone(),
+            params: &[],
+            implementation: AocImplementation::External(external),
+        }
+    }
+
+    pub fn with_params(mut self, params: &'static [AocParam]) -> AocSolver {
+        self.params = params;
+        self
+    }
+
+    pub fn run(&self, input_path: String, params: &AocParams, log: &mut String) -> AocFunctionResult {
+        match &self.implementation {
+            AocImplementation::Native { function, .. } => function(input_path, params, log),
+            AocImplementation::External(external) => external.run(input_path, log),
+        }
+    }
+
+    /// The embedded source, external solvers have none.
+    pub fn source(&self) -> Option<&AocSource> {
+        match &self.implementation {
+            AocImplementation::Native { source, .. } => Some(source),
+            AocImplementation::External(_) => None,
+        }
+    }
+
+    pub fn function_name(&self) -> &str {
+        self.name.rsplit("::").next().unwrap_or(&self.name)
+    }
+
+    pub fn function_span(&self) -> Option<AocSourceSpan> {
+        self.source()?.find_function(self.function_name())
+    }
+}
 
 #[derive(Debug)]
 pub struct AocService {
-    pub input_directory: String,
-    pub problem_answers: HashMap<AocProblem, AocFunction>,
+    pub input_sources: AocInputSources,
+    pub statement_directory: String,
+    /// Where imported statements are written, outside the static files so only the pages serve them.
+    pub imported_statement_directory: String,
+    /// Every variant for a problem, the first registered is the default.
+    pub problem_answers: HashMap<AocProblem, Vec<AocSolver>>,
+    pub history: AocRunHistory,
+    pub search: AocSearch,
+    pub client: AocClient,
+    pub submissions: AocSubmissionLog,
+    pub timers: AocTimers,
 }
 
 impl AocService {
-    pub fn new(input_directory: String) -> AocService {
+    pub fn new(input_sources: AocInputSources, statement_directory: String) -> AocService {
         AocService {
-            input_directory: input_directory,
+            input_sources,
+            statement_directory,
+            imported_statement_directory: String::from(DEFAULT_IMPORTED_STATEMENT_DIRECTORY),
             problem_answers: HashMap::new(),
+            history: AocRunHistory::new(DEFAULT_HISTORY_DIRECTORY),
+            search: AocSearch::new(),
+            client: AocClient::new(AocClientSettings::default()),
+            submissions: AocSubmissionLog::new(&AocClientSettings::default().submission_log),
+            timers: AocTimers::new(DEFAULT_TIMER_FILE),
         }
     }
 
-    pub fn create_default(input_directory: String) -> AocService {
-        let mut service = AocService::new(input_directory);
+    pub fn create_default(input_sources: AocInputSources, statement_directory: String) -> AocService {
+        let mut service = AocService::new(input_sources, statement_directory);
         y2022::configure_service(&mut service);
         service
     }
 
-    pub fn register_answer(&mut self, year: u32, day: u32, part: u8, answer: AocFunction) {
-        self.problem_answers.insert(
-            AocProblem {
-                year: year,
-                day: day,
-                part: part,
+    /// Adds a solver for a problem, replacing any solver with the same variant.
+    pub fn register_answer(&mut self, year: u32, day: u32, part: u8, solver: AocSolver) {
+        let variants = self.problem_answers.entry(AocProblem { year, day, part }).or_default();
+        match variants.iter_mut().find(|v| v.variant == solver.variant) {
+            Some(existing) => *existing = solver,
+            None => variants.push(solver),
+        }
+    }
+
+    /// A solver by variant, or the default one when no variant is given.
+    pub fn get_solver_variant(&self, year: u32, day: u32, part: u8, variant: &Option<String>) -> Option<&AocSolver> {
+        let variants = self.problem_answers.get(&AocProblem { year, day, part })?;
+        match variant {
+            Some(variant) => variants.iter().find(|v| &v.variant == variant),
+            None => variants.first(),
+        }
+    }
+
+    /// Registers a solver from the settings that runs a command.
+    pub fn register_external(&mut self, external: AocExternalSolver) {
+        let (year, day, part) = (external.year, external.day, external.part);
+        self.register_answer(year, day, part, AocSolver::external(external));
+    }
+
+    pub fn list_variants(&self, year: u32, day: u32, part: u8) -> Vec<String> {
+        self.problem_answers
+            .get(&AocProblem { year, day, part })
+            .map(|variants| variants.iter().map(|v| v.variant.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Runs a solver for someone asking for the answer: the run is remembered and can stop the part's timer.
+    pub fn get_answer(&self, request: &AocAnswerRequest) -> AocAnswer {
+        let answer = self.compute_answer(request);
+        self.history.record(&answer);
+        if let Err(error) = self.attach_run(&answer) {
+            println!("Unable to stop the timer for {}/{}/{}: {}", answer.year, answer.day, answer.part, error);
+        }
+        answer
+    }
+
+    /// Runs a solver without recording anything, for repeated runs like benchmarks.
+    pub fn compute_answer(&self, request: &AocAnswerRequest) -> AocAnswer {
+        let AocAnswerRequest { year, day, part, user, dataset, variant, params } = request;
+        let (year, day, part) = (*year, *day, *part);
+
+        // The user name becomes part of a path, so it has to be a plain name.
+        if let Some(user) = user.as_ref().filter(|user| !is_user_name(user)) {
+            return AocAnswer::failed(request, format!("Invalid user name: {}", user));
+        }
+
+        let input_name = self.get_input_name(user, year, day, part, dataset);
+        let mut log = String::new();
+        let solver = self.get_solver_variant(year, day, part, variant);
+
+        // Inputs that aren't plain files are copied out, and the sidecar read, before the clock starts.
+        let input_file = self.input_sources.materialize(&input_name);
+        let metadata = match input_file.as_ref().map(|_| self.read_sidecar(&input_name)) {
+            Ok(Ok(metadata)) => metadata.unwrap_or_default(),
+            Ok(Err(error)) => {
+                log.push_str(&format!("Unable to read the input's metadata: {}\n", error));
+                AocInputMetadata::default()
             },
-            answer,
-        );
-    }
-
-    pub fn get_answer(&self, year: u32, day: u32, part: u8, dataset: &Option<String>) -> AocAnswer {
-
-        let input_file = match dataset {
-            Some(dsname) =>
-                format!("{}/{}/day_{}_{}.{}.txt",
-                    self.input_directory,
-                    year,
-                    day,
-                    part,
-                    dsname),
-            None =>
-                format!("{}/{}/day_{}_{}.txt",
-                    self.input_directory,
-                    year,
-                    day,
-                    part),
+            Err(_) => AocInputMetadata::default(),
+        };
+
+        // The dataset's parameters come first and the request's replace them. A solver only
+        // gets those it declares, external ones and other variants may declare none.
+        let declared = solver.map(|s| s.params).unwrap_or_default();
+        let dataset_params: AocParamInput = metadata.params
+            .iter()
+            .filter(|(name, _)| declared.iter().any(|p| p.name == name.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let mut merged_params = dataset_params.clone();
+        merged_params.extend(params.clone());
+        let params = merged_params;
+
+        // Expected answers are for the dataset as described, so only hold with its parameters.
+        let with_defaults = solver.is_none_or(|s| AocParams::are_defaults(s.params, &params, &dataset_params));
+
+        let expected = if with_defaults {
+            metadata.expected_answer(part).cloned()
+        }
+        else {
+            log.push_str("The parameters are not the dataset's, so there is no expected answer.\n");
+            None
         };
-        
-        let mut log = String::new();
-        let problem = AocProblem { year: year, day: day, part: part };
         let start_time = SystemTime::now();
 
-        let answer_from_result = |result: Result<String, String>, log: String| -> AocAnswer {
+        let answer_from_result = |result: Result<String, String>, params: AocParams, artifacts: Vec<AocArtifact>, log: String| -> AocAnswer {
             let d = SystemTime::now().duration_since(start_time).unwrap_or_default();
             AocAnswer {
-                year: year,
-                day: day,
-                part: part,
-                log: log,
-                result: result,
+                year,
+                day,
+                part,
+                user: user.clone(),
+                dataset: dataset.clone(),
+                variant: solver.map(|s| s.variant.clone()),
+                params,
+                log,
+                result,
+                artifacts,
+                expected: expected.clone(),
+                default_params: with_defaults,
                 execution_time: d.as_secs_f64()
-            } 
+            }
         };
 
-        match self.problem_answers.get(&problem) {
+        match solver {
             None => {
-                answer_from_result(Err(String::from("Problem not found.")), log)
+                let error = match variant {
+                    Some(variant) => format!("Variant {} not found.", variant),
+                    None => String::from("Problem not found."),
+                };
+                answer_from_result(Err(error), AocParams::default(), vec![], log)
             },
-            Some(answer_fn) => {
-                match answer_fn(input_file, &mut log) {
+            Some(solver) => {
+                match AocParams::resolve(solver.params, &params) {
                     Err(error) => {
-                        answer_from_result(Err(error.to_string()), log)
+                        answer_from_result(Err(error.to_string()), AocParams::default(), vec![], log)
                     },
-                    Ok(result) => {
-                        answer_from_result(Ok(result), log)
+                    Ok(params) => {
+                        let result = input_file.and_then(|input_file| solver.run(input_file.path(), &params, &mut log));
+                        match result {
+                            Err(error) => {
+                                answer_from_result(Err(error.to_string()), params, vec![], log)
+                            },
+                            Ok(output) => {
+                                answer_from_result(Ok(output.answer), params, output.artifacts, log)
+                            }
+                        }
                     }
                 }
             }
@@ -119,62 +430,242 @@ impl AocService {
     }
 
     pub fn list_problems(&self) -> Vec<AocProblem> {
-        self.problem_answers.keys().cloned().collect()
+        let mut problems: Vec<AocProblem> = self.problem_answers.keys().cloned().collect();
+        problems.sort();
+        problems
     }
 
-    pub fn list_inputs(&self) -> Result<Vec<AocInput>, Box<dyn Error>> {
+    /// The input's name in the input sources, like 2022/day_11_1.test.txt, or
+    /// alice/2022/day_11_1.txt for a user. Users share the datasets they don't have.
+    pub fn get_input_name(&self, user: &Option<String>, year: u32, day: u32, part: u8, dataset: &Option<String>) -> String {
+        let name = match dataset {
+            Some(dsname) => format!("{}/day_{}_{}.{}.txt", year, day, part, dsname),
+            None => format!("{}/day_{}_{}.txt", year, day, part),
+        };
+
+        match user {
+            Some(user) => {
+                let user_name = format!("{}/{}", user, name);
+                let shared = dataset.is_some() && !matches!(self.input_sources.find(&user_name), Ok(Some(_)));
+                if shared { name } else { user_name }
+            },
+            None => name,
+        }
+    }
+
+    /// Reads an input's sidecar. It can be in another source than the input, a saved answer
+    /// goes to a writable source even when the input is in an archive, so those are read first.
+    pub fn load_input_metadata(&self, input_name: &str) -> Result<Option<AocInputMetadata>, Box<dyn Error>> {
+        if self.input_sources.find(input_name)?.is_none() {
+            return Ok(None);
+        }
+        self.read_sidecar(input_name)
+    }
+
+    /// Reads the sidecar of an input already known to exist.
+    fn read_sidecar(&self, input_name: &str) -> Result<Option<AocInputMetadata>, Box<dyn Error>> {
+        let sidecar_name = AocInputMetadata::sidecar_name(input_name);
+        parse_sidecar(self.input_sources.find_writable_first(&sidecar_name)?, &sidecar_name)
+    }
+
+    /// Expected answers from the input sidecars.
+    pub fn load_expected_answers(&self) -> Result<Vec<AocExpectedAnswer>, Box<dyn Error>> {
+        let mut from_sidecars: Vec<AocExpectedAnswer> = vec![];
+
+        for input in self.list_inputs()? {
+            let Some(metadata) = &input.metadata else {
+                continue;
+            };
+            for (part, answer) in &metadata.expected {
+                from_sidecars.push(AocExpectedAnswer {
+                    year: input.year,
+                    day: input.day,
+                    part: part.parse().map_err(|_| format!("Invalid part {} in the sidecar of {}", part, input.input))?,
+                    dataset: input.dataset.clone(),
+                    answer: answer.clone(),
+                });
+            }
+        }
+
+        // Both parts' sidecars may list the same answer, the first one wins.
+        let mut expected_answers: Vec<AocExpectedAnswer> = vec![];
+        for expected in from_sidecars {
+            if !expected_answers.iter().any(|e| {
+                e.year == expected.year && e.day == expected.day && e.part == expected.part && e.dataset == expected.dataset
+            }) {
+                expected_answers.push(expected);
+            }
+        }
+
+        Ok(expected_answers)
+    }
+
+    pub fn get_statement_path(&self, year: u32, day: u32) -> String {
+        format!("{}/{}/Day {} - Advent of Code {}.html", self.statement_directory, year, day, year)
+    }
+
+    pub fn list_statements(&self) -> Result<Vec<AocStatement>, Box<dyn Error>> {
         let year_re = regex!(r"^\d{4}$");
-        let f_name_re = regex!(r"^day_(\d+)_(\d+)(?:_(.+))?.txt$");
+        let f_name_re = regex!(r"^Day (\d+) - Advent of Code (\d{4})\.html$");
 
-        let mut inputs: Vec<AocInput> = vec![];
+        let mut statements: Vec<AocStatement> = vec![];
 
-        let dir_h = read_dir(&self.input_directory)?;
-        for dir_ent in dir_h {
+        for dir_ent in read_dir(&self.statement_directory)? {
             let dir_ent = dir_ent?;
-            let ft = dir_ent.file_type()?;
             let file_name = dir_ent.file_name();
             let file_name = file_name.to_str().ok_or("Invalid file name")?;
 
-            if ft.is_dir() && year_re.is_match(file_name) {
-                let year = file_name.parse::<u32>()?;
-                let sub_dir_h = read_dir(dir_ent.path())?;
-
-                println!("Found year: {}", year);
-
-                for sub_dir_ent in sub_dir_h {
+            if dir_ent.file_type()?.is_dir() && year_re.is_match(file_name) {
+                for sub_dir_ent in read_dir(dir_ent.path())? {
                     let sub_dir_ent = sub_dir_ent?;
                     let sub_file_name = sub_dir_ent.file_name();
                     let sub_file_name = sub_file_name.to_str().ok_or("Invalid file name")?;
 
-                    println!("looking at: {}", sub_file_name);
-
                     if let Some(caps) = f_name_re.captures(sub_file_name) {
-                        let day = caps
-                            .get(1)
-                            .ok_or("Invalid capture")?
-                            .as_str()
-                            .parse::<u32>()?;
-                        let part = caps
-                            .get(2)
-                            .ok_or("Invalid capture")?
-                            .as_str()
-                            .parse::<u8>()?;
-
-                        inputs.push(AocInput {
-                            year: year,
-                            day: day,
-                            part: part,
-                            input: sub_dir_ent
-                                .path()
-                                .to_str()
-                                .ok_or("Invalid path")?
-                                .to_string(),
+                        statements.push(AocStatement {
+                            year: caps[2].parse()?,
+                            day: caps[1].parse()?,
+                            path: sub_dir_ent.path().to_str().ok_or("Invalid path")?.to_string(),
                         });
                     }
                 }
             }
         }
 
+        statements.sort_by_key(|s| (s.year, s.day));
+        Ok(statements)
+    }
+
+    /// The shared inputs.
+    pub fn list_inputs(&self) -> Result<Vec<AocInput>, Box<dyn Error>> {
+        Ok(self.list_all_inputs()?.into_iter().filter(|i| i.user.is_none()).collect())
+    }
+
+    /// A user's inputs with the shared datasets they don't have themselves.
+    pub fn list_user_inputs(&self, user: &Option<String>) -> Result<Vec<AocInput>, Box<dyn Error>> {
+        let Some(user) = user else {
+            return self.list_inputs();
+        };
+
+        let inputs = self.list_all_inputs()?;
+        let mut user_inputs: Vec<AocInput> = inputs.iter().filter(|i| i.user.as_ref() == Some(user)).cloned().collect();
+        for input in inputs.iter().filter(|i| i.user.is_none() && i.dataset.is_some()) {
+            if !user_inputs.iter().any(|u| (u.year, u.day, u.part, &u.dataset) == (input.year, input.day, input.part, &input.dataset)) {
+                user_inputs.push(input.clone());
+            }
+        }
+
+        user_inputs.sort_by(|a, b| (a.year, a.day, a.part, &a.dataset).cmp(&(b.year, b.day, b.part, &b.dataset)));
+        Ok(user_inputs)
+    }
+
+    /// Every input, shared or a user's.
+    pub fn list_all_inputs(&self) -> Result<Vec<AocInput>, Box<dyn Error>> {
+        let f_name_re = regex!(r"^(?:([^/]+)/)?(\d{4})/day_(\d+)_(\d+)(?:\.(.+))?\.txt$");
+
+        let mut inputs: Vec<AocInput> = vec![];
+
+        let index = self.input_sources.index()?;
+        for name in index.files() {
+            let Some(caps) = f_name_re.captures(&name) else {
+                continue;
+            };
+            let user = caps.get(1).map(|u| u.as_str().to_string());
+            if user.as_ref().is_some_and(|u| !is_user_name(u)) {
+                continue;
+            }
+            let Some(layer) = index.find(&name) else {
+                continue;
+            };
+
+            // A broken sidecar shouldn't hide the input, so it is only reported.
+            let sidecar_name = AocInputMetadata::sidecar_name(&name);
+            let metadata = parse_sidecar(index.find_writable_first(&sidecar_name), &sidecar_name).unwrap_or_else(|error| {
+                println!("Skipping metadata: {}", error);
+                None
+            });
+
+            inputs.push(AocInput {
+                year: caps[2].parse()?,
+                day: caps[3].parse()?,
+                part: caps[4].parse()?,
+                user,
+                dataset: caps.get(5).map(|ds| ds.as_str().to_string()),
+                input: layer.location(&name),
+                metadata,
+            });
+        }
+
+        inputs.sort_by(|a, b| (a.year, a.day, a.part, &a.user, &a.dataset).cmp(&(b.year, b.day, b.part, &b.user, &b.dataset)));
         Ok(inputs)
     }
 }
+
+/// Reads a sidecar from the layer it was found in, if any.
+fn parse_sidecar(layer: Option<&dyn AocInputSource>, sidecar_name: &str) -> Result<Option<AocInputMetadata>, Box<dyn Error>> {
+    let Some(layer) = layer else {
+        return Ok(None);
+    };
+    match layer.read_file(sidecar_name)? {
+        Some(contents) => {
+            let contents = String::from_utf8(contents)?;
+            Ok(Some(AocInputMetadata::parse(&contents, &layer.location(sidecar_name))?))
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input_source::archive::ArchiveInputSource;
+    use input_source::directory::DirectoryInputSource;
+
+    fn monkey_business(_input_path: String, _params: &AocParams, _log: &mut String) -> AocFunctionResult {
+        Ok(AocOutput::new(10197))
+    }
+
+    #[test]
+    fn solvers_only_get_the_dataset_params_they_declare() {
+        let source = DirectoryInputSource::new("www/aoc_input");
+        let mut aoc_service = AocService::create_default(AocInputSources::new(vec![Box::new(source)]), String::from("www/aoc_problems"));
+        aoc_service.register_answer(2022, 11, 2, AocSolver {
+            name: String::from("monkey_business"),
+            variant: String::from("plain"),
+            params: &[],
+            implementation: AocImplementation::Native {
+                function: monkey_business,
+                source: AocSource { path: "y2022/day11.rs", text: "" },
+            },
+        });
+        let request = |variant: Option<&str>| AocAnswerRequest {
+            year: 2022,
+            day: 11,
+            part: 2,
+            user: None,
+            dataset: Some(String::from("rounds")),
+            variant: variant.map(String::from),
+            params: Default::default(),
+        };
+
+        let plain = aoc_service.compute_answer(&request(Some("plain")));
+        assert_eq!(plain.result, Ok(String::from("10197")));
+        assert!(plain.params.0.is_empty());
+        assert_eq!(plain.verdict(), AocVerdict::Correct);
+
+        let default = aoc_service.compute_answer(&request(None));
+        assert_eq!(default.params.integer("rounds").unwrap(), 20);
+        assert_eq!(default.verdict(), AocVerdict::Correct);
+    }
+
+    #[test]
+    fn lists_inputs_past_a_missing_archive() {
+        let archive = ArchiveInputSource::new("missing/inputs.tar", None);
+        let directory = DirectoryInputSource::new("www/aoc_input");
+        let aoc_service = AocService::new(AocInputSources::new(vec![Box::new(archive), Box::new(directory)]), String::from("www/aoc_problems"));
+
+        let inputs = aoc_service.list_inputs().unwrap();
+        let rounds = inputs.iter().find(|i| (i.year, i.day, i.part, i.dataset.as_deref()) == (2022, 11, 2, Some("rounds"))).unwrap();
+        assert_eq!(rounds.metadata.as_ref().and_then(|m| m.expected_answer(2)), Some(&String::from("10197")));
+    }
+}
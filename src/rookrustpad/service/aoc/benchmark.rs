@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::{AocAnswer, AocAnswerRequest, AocService};
+
+pub const MAX_BENCHMARK_ITERATIONS: u32 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocBenchmark {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+    pub variant: Option<String>,
+    pub iterations: u32,
+    pub min_time: f64,
+    pub max_time: f64,
+    pub mean_time: f64,
+    pub median_time: f64,
+    /// The answer from the last iteration.
+    pub answer: AocAnswer,
+}
+
+impl AocService {
+    /// Runs a solver several times in a row, stopping at the first error. Only the last run is
+    /// remembered, and it doesn't stop timers, a benchmark isn't someone solving the part.
+    pub fn benchmark(&self, request: &AocAnswerRequest, iterations: u32) -> AocBenchmark {
+        let iterations = iterations.clamp(1, MAX_BENCHMARK_ITERATIONS);
+        let mut times: Vec<f64> = vec![];
+        let mut answer = self.compute_answer(request);
+        times.push(answer.execution_time);
+
+        while answer.result.is_ok() && (times.len() as u32) < iterations {
+            answer = self.compute_answer(request);
+            times.push(answer.execution_time);
+        }
+        self.history.record(&answer);
+
+        times.sort_by(|a, b| a.total_cmp(b));
+        let total: f64 = times.iter().sum();
+
+        AocBenchmark {
+            year: request.year,
+            day: request.day,
+            part: request.part,
+            user: request.user.clone(),
+            dataset: request.dataset.clone(),
+            variant: answer.variant.clone(),
+            iterations: times.len() as u32,
+            min_time: times[0],
+            max_time: times[times.len() - 1],
+            mean_time: total / times.len() as f64,
+            median_time: times[times.len() / 2],
+            answer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::history::AocRunHistory;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+    use crate::rookrustpad::service::aoc::timer::{AocTimerAction, AocTimers};
+    use crate::rookrustpad::service::aoc::AocVerdict;
+
+    #[test]
+    fn benchmarks_leave_timers_alone_and_record_once() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = |name: &str| directory.path().join(name).to_string_lossy().into_owned();
+        fs::create_dir_all(directory.path().join("input/2022")).unwrap();
+        fs::copy("fixtures/aoc/2022/day_11.input.txt", directory.path().join("input/2022/day_11_1.txt")).unwrap();
+        fs::write(directory.path().join("input/2022/day_11_1.toml"), "[expected]\n1 = 66124\n").unwrap();
+
+        let source = DirectoryInputSource::new(&path("input"));
+        let mut aoc_service = AocService::create_default(AocInputSources::new(vec![Box::new(source)]), path("problems"));
+        aoc_service.timers = AocTimers::new(&path("timers.json"));
+        aoc_service.history = AocRunHistory::new(&path("history"));
+        aoc_service.update_timer(2022, 11, 1, &None, AocTimerAction::Start).unwrap();
+        let request = AocAnswerRequest { year: 2022, day: 11, part: 1, user: None, dataset: None, variant: None, params: Default::default() };
+
+        let benchmark = aoc_service.benchmark(&request, 3);
+        assert_eq!(benchmark.iterations, 3);
+        assert_eq!(benchmark.answer.verdict(), AocVerdict::Correct);
+        let timer = aoc_service.get_timer(2022, 11, 1, &None).unwrap().unwrap();
+        assert!(timer.solve.is_none());
+        let history = fs::read_to_string(directory.path().join("history/shared.jsonl")).unwrap();
+        assert_eq!(history.lines().count(), 1);
+
+        aoc_service.get_answer(&request);
+        let timer = aoc_service.get_timer(2022, 11, 1, &None).unwrap().unwrap();
+        assert_eq!(timer.solve.map(|solve| (solve.answer, solve.source)), Some((String::from("66124"), String::from("run"))));
+    }
+}
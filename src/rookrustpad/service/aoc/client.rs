@@ -0,0 +1,270 @@
+use std::error::Error;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::status::PARTS_PER_DAY;
+use crate::rookrustpad::service::aoc::AocService;
+
+/// Settings for talking to the Advent of Code website, the [aoc] table of the settings.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct AocClientSettings {
+    /// Where requests go, a mock server's address when testing.
+    pub base_url: String,
+    /// The value of the session cookie of a logged in browser, $AOC_SESSION when not set.
+    pub session: Option<String>,
+    /// AoC asks for a way to contact whoever runs automated requests in the User-Agent.
+    pub user_agent: String,
+    /// Seconds to wait between any two requests.
+    pub min_interval: f64,
+    /// Seconds before a request is given up on.
+    pub timeout: u64,
+    /// Where every submitted answer and AoC's reply are written, one json object per line.
+    pub submission_log: String,
+    /// The number in a private leaderboard's address, its owner's user id.
+    pub leaderboard_id: Option<String>,
+    /// Where imported leaderboards are kept, one file for each year.
+    pub leaderboard_directory: String,
+}
+
+impl Default for AocClientSettings {
+    fn default() -> Self {
+        AocClientSettings {
+            base_url: String::from("https://adventofcode.com"),
+            session: None,
+            user_agent: String::from("github.com/castle8080/rookrustpad"),
+            min_interval: 5.0,
+            timeout: 30,
+            submission_log: String::from("data/aoc_submissions.jsonl"),
+            leaderboard_id: None,
+            leaderboard_directory: String::from("data/leaderboards"),
+        }
+    }
+}
+
+/// Talks to the Advent of Code website, starting requests no closer together than min_interval.
+#[derive(Debug)]
+pub struct AocClient {
+    pub settings: AocClientSettings,
+    agent: ureq::Agent,
+    /// When the latest request was or is due to be sent.
+    last_request: Mutex<Option<Instant>>,
+}
+
+/// An input fetched for a day, or found already saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocDownload {
+    pub year: u32,
+    pub day: u32,
+    /// The input was already there so nothing was fetched.
+    pub cached: bool,
+    /// Where each part's input is.
+    pub locations: Vec<String>,
+}
+
+impl AocClient {
+    pub fn new(settings: AocClientSettings) -> AocClient {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(&settings.user_agent)
+            .timeout(Duration::from_secs(settings.timeout))
+            .build();
+
+        AocClient { settings, agent, last_request: Mutex::new(None) }
+    }
+
+    fn session(&self) -> Result<String, Box<dyn Error>> {
+        self.settings.session
+            .clone()
+            .or_else(|| std::env::var("AOC_SESSION").ok())
+            .filter(|session| !session.trim().is_empty())
+            .map(|session| session.trim().to_string())
+            .ok_or_else(|| "No AoC session, set session under [aoc] or $AOC_SESSION".into())
+    }
+
+    /// Takes the next time a request may be sent, min_interval after the one before it.
+    /// Requests from different threads each get their own time, in the order they asked.
+    fn reserve_slot(&self) -> Result<Instant, Box<dyn Error>> {
+        let mut last_request = self.last_request.lock().map_err(|_| "The AoC client is unavailable")?;
+        let now = Instant::now();
+        let interval = Duration::from_secs_f64(self.settings.min_interval.max(0.0));
+        let slot = match *last_request {
+            Some(last) => (last + interval).max(now),
+            None => now,
+        };
+        *last_request = Some(slot);
+        Ok(slot)
+    }
+
+    /// Waits for this request's slot, without holding the lock while sleeping.
+    fn throttle(&self) -> Result<(), Box<dyn Error>> {
+        let slot = self.reserve_slot()?;
+        let wait = slot.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+        Ok(())
+    }
+
+    /// Sends a request as the logged in user, path starts with a /. The form is only sent with POST.
+    fn send(&self, method: &str, path: &str, form: &[(&str, &str)]) -> Result<String, Box<dyn Error>> {
+        let session = self.session()?;
+        let url = format!("{}{}", self.settings.base_url.trim_end_matches('/'), path);
+
+        self.throttle()?;
+        println!("{} {}", method, url);
+        let request = self.agent.request(method, &url).set("Cookie", &format!("session={}", session));
+        let response = if method == "POST" { request.send_form(form) } else { request.call() };
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(404, _)) => Err(format!("{} is not available, the puzzle may not be unlocked yet", url).into()),
+            Err(ureq::Error::Status(400, _)) | Err(ureq::Error::Status(500, _)) =>
+                Err(format!("{} refused the session, it may have expired", url).into()),
+            Err(ureq::Error::Status(status, _)) => Err(format!("{} returned {}", url, status).into()),
+            Err(error) => Err(format!("Unable to reach {}: {}", url, error).into()),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        self.send("GET", path, &[])
+    }
+
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String, Box<dyn Error>> {
+        self.send("POST", path, form)
+    }
+
+    pub fn fetch_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        self.get(&format!("/{}/day/{}/input", year, day))
+    }
+}
+
+impl AocService {
+    /// Saves a day's input as each part's input in the first writable input source.
+    /// Inputs only change when the account does, so one already in any source is never fetched again.
+    pub fn download_input(&self, year: u32, day: u32) -> Result<AocDownload, Box<dyn Error>> {
+        let names: Vec<String> = (1..=PARTS_PER_DAY).map(|part| self.get_input_name(&None, year, day, part, &None)).collect();
+
+        let mut existing = vec![];
+        for name in &names {
+            if let Some(layer) = self.input_sources.find(name)? {
+                existing.push((name, layer.location(name)));
+            }
+        }
+        if existing.len() == names.len() {
+            return Ok(AocDownload { year, day, cached: true, locations: existing.into_iter().map(|(_, l)| l).collect() });
+        }
+
+        // Both parts read the same input, so another part's copy saves a request.
+        let cached = !existing.is_empty();
+        let input = match existing.first() {
+            Some((name, _)) => self.input_sources.read_file(name)?.ok_or_else(|| format!("Input {} not found", name))?,
+            None => self.client.fetch_input(year, day)?.into_bytes(),
+        };
+
+        let mut locations = vec![];
+        for name in &names {
+            match existing.iter().find(|(existing_name, _)| *existing_name == name) {
+                Some((_, location)) => locations.push(location.clone()),
+                None => locations.push(self.input_sources.write_file(name, &input)?),
+            }
+        }
+
+        Ok(AocDownload { year, day, cached, locations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    fn client(min_interval: f64) -> AocClient {
+        AocClient::new(AocClientSettings {
+            // Nothing listens here, a request that gets sent fails.
+            base_url: String::from("http://127.0.0.1:9"),
+            session: Some(String::from("test")),
+            min_interval,
+            timeout: 1,
+            ..AocClientSettings::default()
+        })
+    }
+
+    fn service(directory: &std::path::Path) -> AocService {
+        let source = DirectoryInputSource::new(&directory.to_string_lossy());
+        let mut aoc_service = AocService::new(AocInputSources::new(vec![Box::new(source)]), String::from("www/aoc_problems"));
+        aoc_service.client = client(0.0);
+        aoc_service
+    }
+
+    #[test]
+    fn saved_inputs_are_not_fetched_again() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("2022")).unwrap();
+        fs::write(directory.path().join("2022/day_1_1.txt"), "1000\n").unwrap();
+        fs::write(directory.path().join("2022/day_1_2.txt"), "1000\n").unwrap();
+
+        let download = service(directory.path()).download_input(2022, 1).unwrap();
+        assert!(download.cached);
+        assert_eq!(download.locations.len(), 2);
+    }
+
+    #[test]
+    fn the_other_part_is_copied_instead_of_fetched() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("2022")).unwrap();
+        fs::write(directory.path().join("2022/day_1_1.txt"), "1000\n").unwrap();
+
+        let download = service(directory.path()).download_input(2022, 1).unwrap();
+        assert!(download.cached);
+        assert_eq!(fs::read_to_string(directory.path().join("2022/day_1_2.txt")).unwrap(), "1000\n");
+    }
+
+    #[test]
+    fn missing_inputs_are_fetched() {
+        let directory = tempfile::tempdir().unwrap();
+        let error = service(directory.path()).download_input(2022, 1).unwrap_err();
+        assert!(error.to_string().starts_with("Unable to reach http://127.0.0.1:9/2022/day/1/input"), "{}", error);
+    }
+
+    #[test]
+    fn requests_are_spaced_by_the_interval() {
+        let client = Arc::new(client(0.2));
+        let started = Instant::now();
+        let threads: Vec<_> = (0..3)
+            .map(|_| {
+                let client = client.clone();
+                thread::spawn(move || {
+                    client.throttle().unwrap();
+                    started.elapsed()
+                })
+            })
+            .collect();
+        let mut sent: Vec<Duration> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        sent.sort();
+
+        assert!(sent[0] < Duration::from_millis(100), "{:?}", sent);
+        assert!(sent[1] >= Duration::from_millis(200), "{:?}", sent);
+        assert!(sent[2] >= Duration::from_millis(400), "{:?}", sent);
+    }
+
+    #[test]
+    fn waiting_does_not_hold_the_lock() {
+        let client = Arc::new(client(0.5));
+        client.throttle().unwrap();
+
+        let waiting = {
+            let client = client.clone();
+            thread::spawn(move || client.throttle().unwrap())
+        };
+        thread::sleep(Duration::from_millis(100));
+        assert!(client.last_request.try_lock().is_ok());
+        waiting.join().unwrap();
+    }
+}
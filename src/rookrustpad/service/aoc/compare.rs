@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::benchmark::AocBenchmark;
+use crate::rookrustpad::service::aoc::{AocAnswerRequest, AocService};
+
+/// Every variant of a problem benchmarked on the same input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocComparison {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+    /// True when every variant answered and all the answers are the same.
+    pub agree: bool,
+    /// In the order the variants were registered.
+    pub benchmarks: Vec<AocBenchmark>,
+}
+
+impl AocService {
+    /// Every variant gets the same input and parameters, the request's variant is ignored.
+    pub fn compare(&self, request: &AocAnswerRequest, iterations: u32) -> AocComparison {
+        let benchmarks: Vec<AocBenchmark> = self.list_variants(request.year, request.day, request.part)
+            .into_iter()
+            .map(|variant| self.benchmark(&AocAnswerRequest { variant: Some(variant), ..request.clone() }, iterations))
+            .collect();
+
+        let mut answers = benchmarks.iter().map(|b| b.answer.result.as_ref().map(|r| r.trim()));
+        let agree = match answers.next() {
+            Some(Ok(first)) => answers.all(|a| a == Ok(first)),
+            _ => false,
+        };
+
+        AocComparison {
+            year: request.year,
+            day: request.day,
+            part: request.part,
+            user: request.user.clone(),
+            dataset: request.dataset.clone(),
+            agree,
+            benchmarks,
+        }
+    }
+}
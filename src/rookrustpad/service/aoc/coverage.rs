@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::rookrustpad::service::aoc::{AocExpectedAnswer, AocInput, AocProblem, AocService, AocStatement};
+
+/// How the saved statements, inputs, expected answers and solvers line up.
+#[derive(Debug, Clone, Serialize)]
+pub struct AocCoverage {
+    pub statement_count: usize,
+    pub solver_count: usize,
+    pub input_count: usize,
+    pub expected_answer_count: usize,
+    pub statements_without_solvers: Vec<AocStatement>,
+    /// Their statement link on the calendar would go nowhere.
+    pub solvers_without_statements: Vec<AocProblem>,
+    pub solvers_without_inputs: Vec<AocProblem>,
+    pub inputs_without_solvers: Vec<AocInput>,
+    pub inputs_without_expected_answers: Vec<AocInput>,
+    pub expected_answers_without_inputs: Vec<AocExpectedAnswer>,
+}
+
+impl AocCoverage {
+    pub fn is_complete(&self) -> bool {
+        self.statements_without_solvers.is_empty()
+            && self.solvers_without_statements.is_empty()
+            && self.solvers_without_inputs.is_empty()
+            && self.inputs_without_solvers.is_empty()
+            && self.inputs_without_expected_answers.is_empty()
+            && self.expected_answers_without_inputs.is_empty()
+    }
+}
+
+impl AocService {
+    pub fn get_coverage(&self) -> Result<AocCoverage, Box<dyn Error>> {
+        let statements = self.list_statements()?;
+        let problems = self.list_problems();
+        let inputs = self.list_inputs()?;
+        let expected_answers = self.load_expected_answers()?;
+
+        let has_solver = |year: u32, day: u32, part: u8| {
+            self.problem_answers.contains_key(&AocProblem { year, day, part })
+        };
+
+        let has_expected = |input: &AocInput| {
+            expected_answers.iter().any(|e| {
+                e.year == input.year && e.day == input.day && e.part == input.part && e.dataset == input.dataset
+            })
+        };
+
+        Ok(AocCoverage {
+            statement_count: statements.len(),
+            solver_count: problems.len(),
+            input_count: inputs.len(),
+            expected_answer_count: expected_answers.len(),
+            statements_without_solvers: statements
+                .iter()
+                .filter(|s| !problems.iter().any(|p| p.year == s.year && p.day == s.day))
+                .cloned()
+                .collect(),
+            solvers_without_statements: problems
+                .iter()
+                .filter(|p| !Path::new(&self.get_statement_path(p.year, p.day)).exists())
+                .cloned()
+                .collect(),
+            solvers_without_inputs: problems
+                .iter()
+                .filter(|p| !inputs.iter().any(|i| i.year == p.year && i.day == p.day && i.part == p.part))
+                .cloned()
+                .collect(),
+            inputs_without_solvers: inputs
+                .iter()
+                .filter(|i| !has_solver(i.year, i.day, i.part))
+                .cloned()
+                .collect(),
+            inputs_without_expected_answers: inputs
+                .iter()
+                .filter(|i| !has_expected(i))
+                .cloned()
+                .collect(),
+            expected_answers_without_inputs: expected_answers
+                .iter()
+                .filter(|e| !inputs.iter().any(|i| {
+                    i.year == e.year && i.day == e.day && i.part == e.part && i.dataset == e.dataset
+                }))
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    #[test]
+    fn lists_what_is_missing() {
+        let inputs = tempfile::tempdir().unwrap();
+        let statements = tempfile::tempdir().unwrap();
+        fs::create_dir(inputs.path().join("2022")).unwrap();
+        fs::create_dir(statements.path().join("2022")).unwrap();
+        for day in [11, 12] {
+            fs::write(statements.path().join(format!("2022/Day {} - Advent of Code 2022.html", day)), "").unwrap();
+        }
+        fs::write(inputs.path().join("2022/day_11_1.txt"), "").unwrap();
+        fs::write(inputs.path().join("2022/day_11_1.toml"), "[expected]\n1 = \"10605\"\n").unwrap();
+        fs::write(inputs.path().join("2022/day_11_2.txt"), "").unwrap();
+
+        let source = DirectoryInputSource::new(&inputs.path().to_string_lossy());
+        let aoc_service = AocService::create_default(
+            AocInputSources::new(vec![Box::new(source)]),
+            statements.path().to_string_lossy().to_string(),
+        );
+
+        let coverage = aoc_service.get_coverage().unwrap();
+        assert_eq!((coverage.statement_count, coverage.input_count, coverage.expected_answer_count), (2, 2, 1));
+        let days: Vec<u32> = coverage.statements_without_solvers.iter().map(|s| s.day).collect();
+        assert_eq!(days, vec![12]);
+        let parts: Vec<u8> = coverage.inputs_without_expected_answers.iter().map(|i| i.part).collect();
+        assert_eq!(parts, vec![2]);
+        assert!(coverage.solvers_without_statements.is_empty());
+        assert!(coverage.solvers_without_inputs.is_empty());
+        assert!(!coverage.is_complete());
+    }
+}
@@ -0,0 +1,211 @@
+use std::error::Error;
+use std::fs;
+
+use lazy_regex::regex;
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::metadata::AocInputMetadata;
+use crate::rookrustpad::service::aoc::statement::html_text;
+use crate::rookrustpad::service::aoc::AocService;
+
+/// The dataset extracted examples are saved as.
+pub const EXAMPLE_DATASET: &str = "test";
+
+/// An example dataset read out of a saved statement, ready to be saved next to the inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocExampleProposal {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub dataset: String,
+    pub input: String,
+    /// The last highlighted answer in the part's description.
+    pub expected: Option<String>,
+    /// There is already an input with this name.
+    pub exists: bool,
+    /// Where it was saved, when it was.
+    pub written_to: Option<String>,
+}
+
+/// The example input of a part's description: the code block introduced by a
+/// paragraph like "For example:". Other blocks walk through the example, so with
+/// fallback the first one without highlighting is taken only when none is introduced.
+fn example_input(article: &str, fallback: bool) -> Option<String> {
+    let mut first = None;
+    let mut position = 0;
+
+    for caps in regex!(r"(?s)<pre><code>(.*?)</code></pre>").captures_iter(article) {
+        let block = caps.get(0)?;
+        let before = &article[position..block.start()];
+        position = block.end();
+
+        let introduction = regex!(r"(?s)<p>(.*?)</p>")
+            .captures_iter(before)
+            .last()
+            .map(|p| html_text(&p[1]).to_lowercase())
+            .unwrap_or_default();
+        if introduction.contains("for example") && introduction.trim_end().ends_with(':') {
+            return Some(html_text(&caps[1]));
+        }
+        if fallback && !caps[1].contains("<em>") {
+            first.get_or_insert_with(|| html_text(&caps[1]));
+        }
+    }
+
+    first
+}
+
+/// Puzzle descriptions close on the example's answer, so the last highlighted code wins.
+fn example_answer(article: &str) -> Option<String> {
+    regex!(r"<code><em>([^<]*)</em></code>")
+        .captures_iter(article)
+        .last()
+        .map(|caps| html_text(&caps[1]).trim().to_string())
+}
+
+/// The description of each part, in order.
+fn part_articles(statement: &str) -> Vec<&str> {
+    regex!(r#"(?s)<article class="day-desc">.*?</article>"#)
+        .find_iter(statement)
+        .map(|m| m.as_str())
+        .collect()
+}
+
+impl AocService {
+    /// Proposes a test dataset for each part described in the saved statement. Part 2
+    /// usually reuses part 1's example, so it gets that input when it shows none of its own.
+    pub fn extract_examples(&self, year: u32, day: u32) -> Result<Vec<AocExampleProposal>, Box<dyn Error>> {
+        let statement_path = self.get_statement_path(year, day);
+        let statement = fs::read_to_string(&statement_path)
+            .map_err(|e| format!("Unable to read {}: {}", statement_path, e))?;
+
+        let mut proposals: Vec<AocExampleProposal> = vec![];
+        for (n, article) in part_articles(&statement).into_iter().enumerate() {
+            let part = n as u8 + 1;
+            let input = match example_input(article, proposals.is_empty()) {
+                Some(input) => input,
+                None => match proposals.last() {
+                    Some(previous) => previous.input.clone(),
+                    None => continue,
+                },
+            };
+
+            let dataset = Some(EXAMPLE_DATASET.to_string());
+            let input_name = self.get_input_name(&None, year, day, part, &dataset);
+            proposals.push(AocExampleProposal {
+                year,
+                day,
+                part,
+                dataset: EXAMPLE_DATASET.to_string(),
+                input,
+                expected: example_answer(article),
+                exists: self.input_sources.find(&input_name)?.is_some(),
+                written_to: None,
+            });
+        }
+
+        Ok(proposals)
+    }
+
+    /// Saves the proposed examples with a sidecar holding the expected answer. A sidecar already
+    /// there keeps its description, parameters and other answers. Existing datasets are left
+    /// alone unless overwrite is set.
+    pub fn write_examples(&self, year: u32, day: u32, overwrite: bool) -> Result<Vec<AocExampleProposal>, Box<dyn Error>> {
+        let mut proposals = self.extract_examples(year, day)?;
+
+        for proposal in proposals.iter_mut().filter(|p| overwrite || !p.exists) {
+            let dataset = Some(proposal.dataset.clone());
+            let input_name = self.get_input_name(&None, year, day, proposal.part, &dataset);
+
+            let mut metadata = self.read_sidecar(&input_name)?.unwrap_or_default();
+            metadata.description.get_or_insert_with(|| String::from("The example from the puzzle statement"));
+            metadata.source.get_or_insert_with(|| String::from("example from statement"));
+            if let Some(expected) = &proposal.expected {
+                metadata.expected.insert(proposal.part.to_string(), expected.clone());
+            }
+
+            proposal.written_to = Some(self.input_sources.write_file(&input_name, proposal.input.as_bytes())?);
+            self.input_sources.write_file(
+                &AocInputMetadata::sidecar_name(&input_name),
+                toml::to_string(&metadata)?.as_bytes())?;
+            // The sidecar's description is searched.
+            self.search.invalidate();
+        }
+
+        Ok(proposals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    fn service(inputs: &tempfile::TempDir, statements: &str) -> AocService {
+        let source = DirectoryInputSource::new(&inputs.path().to_string_lossy());
+        AocService::new(AocInputSources::new(vec![Box::new(source)]), statements.to_string())
+    }
+
+    #[test]
+    fn finds_the_examples_of_the_saved_statements() {
+        let inputs = tempfile::tempdir().unwrap();
+        let aoc_service = service(&inputs, "www/aoc_problems");
+        let monkeys = fs::read_to_string("www/aoc_input/2022/day_11_1.test.txt").unwrap();
+
+        let day11 = aoc_service.extract_examples(2022, 11).unwrap();
+        assert_eq!(day11.len(), 2);
+        assert_eq!(day11[0].input.trim_end(), monkeys.trim_end());
+        assert_eq!(day11[0].expected.as_deref(), Some("10605"));
+        // Part 2 shows no example of its own.
+        assert_eq!(day11[1].input, day11[0].input);
+        assert_eq!(day11[1].expected.as_deref(), Some("2713310158"));
+        assert!(day11.iter().all(|p| p.dataset == EXAMPLE_DATASET && !p.exists));
+
+        let day12 = aoc_service.extract_examples(2022, 12).unwrap();
+        assert_eq!(day12.len(), 1);
+        assert!(day12[0].input.starts_with("Sabqponm\nabcryxxl\n"), "{}", day12[0].input);
+        assert_eq!(day12[0].expected.as_deref(), Some("31"));
+    }
+
+    #[test]
+    fn proposes_nothing_without_an_example() {
+        let inputs = tempfile::tempdir().unwrap();
+        let statements = tempfile::tempdir().unwrap();
+        fs::create_dir(statements.path().join("2022")).unwrap();
+        fs::write(
+            statements.path().join("2022/Day 25 - Advent of Code 2022.html"),
+            r#"<main><article class="day-desc"><h2>--- Day 25 ---</h2><p>There is no example, the answer is <code><em>42</em></code>.</p></article></main>"#,
+        ).unwrap();
+        let aoc_service = service(&inputs, &statements.path().to_string_lossy());
+
+        assert!(aoc_service.extract_examples(2022, 25).unwrap().is_empty());
+        assert!(aoc_service.write_examples(2022, 25, true).unwrap().is_empty());
+        assert!(!inputs.path().join("2022").exists());
+    }
+
+    #[test]
+    fn writing_examples_keeps_what_the_sidecar_already_holds() {
+        let inputs = tempfile::tempdir().unwrap();
+        fs::create_dir(inputs.path().join("2022")).unwrap();
+        fs::write(inputs.path().join("2022/day_11_2.test.txt"), "Monkey 0:\n").unwrap();
+        fs::write(inputs.path().join("2022/day_11_2.test.toml"), "description = \"Mine\"\n\n[expected]\n1 = 1\n\n[params]\nrounds = 20\n").unwrap();
+        let aoc_service = service(&inputs, "www/aoc_problems");
+
+        let proposals = aoc_service.write_examples(2022, 11, false).unwrap();
+        assert_eq!(proposals.iter().map(|p| p.written_to.is_some()).collect::<Vec<bool>>(), vec![true, false]);
+        assert!(aoc_service.load_input_metadata("2022/day_11_2.test.txt").unwrap().unwrap().expected_answer(2).is_none());
+
+        aoc_service.write_examples(2022, 11, true).unwrap();
+        let metadata = aoc_service.load_input_metadata("2022/day_11_2.test.txt").unwrap().unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("Mine"));
+        assert_eq!(metadata.source.as_deref(), Some("example from statement"));
+        assert_eq!(metadata.expected_answer(1), Some(&String::from("1")));
+        assert_eq!(metadata.expected_answer(2), Some(&String::from("2713310158")));
+        assert_eq!(metadata.params.get("rounds").map(String::as_str), Some("20"));
+
+        let part1 = aoc_service.load_input_metadata("2022/day_11_1.test.txt").unwrap().unwrap();
+        assert_eq!(part1.description.as_deref(), Some("The example from the puzzle statement"));
+        assert_eq!(part1.expected_answer(1), Some(&String::from("10605")));
+    }
+}
@@ -0,0 +1,191 @@
+use std::fs;
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::output::AocOutput;
+use crate::rookrustpad::service::aoc::AocFunctionResult;
+
+/// Placeholder in the command's arguments for the input file's path.
+pub const INPUT_PLACEHOLDER: &str = "{input}";
+
+/// How long the pipes get to close once everything the command started has been killed.
+const PIPE_GRACE: Duration = Duration::from_secs(1);
+
+fn default_timeout() -> u64 {
+    60
+}
+
+/// A solver written in another language, run as a command for every answer.
+/// The answer is what it prints on stdout and stderr goes to the log.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AocExternalSolver {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub variant: String,
+    /// The program and its arguments, {input} is replaced by the input file's path.
+    pub command: Vec<String>,
+    /// Also write the input to the command's stdin.
+    #[serde(default)]
+    pub stdin: bool,
+    /// Seconds before the command is killed.
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    pub working_directory: Option<String>,
+}
+
+impl AocExternalSolver {
+    pub fn command_line(&self) -> String {
+        self.command.join(" ")
+    }
+
+    pub fn run(&self, input_path: String, log: &mut String) -> AocFunctionResult {
+        let (program, args) = self.command.split_first().ok_or("The external solver has no command")?;
+
+        let mut command = Command::new(program);
+        command
+            .args(args.iter().map(|arg| arg.replace(INPUT_PLACEHOLDER, &input_path)))
+            .stdin(if self.stdin { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(working_directory) = &self.working_directory {
+            command.current_dir(working_directory);
+        }
+        // Its own process group, so a timeout also stops whatever a wrapper like sh -c or cargo run started.
+        #[cfg(unix)]
+        command.process_group(0);
+
+        let input = if self.stdin { Some(fs::read(&input_path)?) } else { None };
+        let mut child = command.spawn().map_err(|e| format!("Unable to start {}: {}", program, e))?;
+
+        // Pipes are fed and drained on their own threads so a chatty command can't block on a full pipe.
+        // The writer isn't waited for, a command that never reads its stdin would hold it up.
+        let stdin = child.stdin.take();
+        thread::spawn(move || {
+            if let (Some(mut stdin), Some(input)) = (stdin, input) {
+                // The command may exit without reading everything, that is up to it.
+                let _ = stdin.write_all(&input);
+            }
+        });
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        let mut status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if Instant::now() >= deadline {
+                kill_group(&mut child);
+                break None;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        // Anything the command left running still holds the pipes, so the output only gets until the deadline.
+        let mut stdout_text = stdout.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok();
+        let mut stderr_text = stderr.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok();
+        if stdout_text.is_none() || stderr_text.is_none() {
+            kill_group(&mut child);
+            status = None;
+            stdout_text = stdout_text.or_else(|| stdout.recv_timeout(PIPE_GRACE).ok());
+            stderr_text = stderr_text.or_else(|| stderr.recv_timeout(PIPE_GRACE).ok());
+        }
+        let stdout = stdout_text.unwrap_or_default();
+        log.push_str(&stderr_text.unwrap_or_default());
+
+        match status {
+            None => Err(format!("Timed out after {} seconds", self.timeout).into()),
+            Some(status) if !status.success() => Err(format!("{} exited with {}", program, status).into()),
+            Some(_) => {
+                let answer = stdout.trim();
+                if answer.is_empty() {
+                    return Err(format!("{} printed no answer", program).into());
+                }
+                Ok(AocOutput::new(answer))
+            }
+        }
+    }
+}
+
+/// Kills the command and everything it started, then reaps it.
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        // The group was made with the child's pid as its id.
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Reads a pipe to its end on a thread of its own, the text arrives on the receiver.
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let mut bytes = vec![];
+            if pipe.read_to_end(&mut bytes).is_ok() {
+                text = String::from_utf8_lossy(&bytes).into_owned();
+            }
+        }
+        let _ = sender.send(text);
+    });
+    receiver
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn solver(script: &str, timeout: u64) -> AocExternalSolver {
+        AocExternalSolver {
+            year: 2022,
+            day: 1,
+            part: 1,
+            variant: String::from("sh"),
+            command: vec![String::from("sh"), String::from("-c"), script.to_string(), String::from("sh"), String::from(INPUT_PLACEHOLDER)],
+            stdin: false,
+            timeout,
+            working_directory: None,
+        }
+    }
+
+    #[test]
+    fn answers_with_stdout_and_logs_stderr() {
+        let mut log = String::new();
+        let output = solver("echo working >&2; wc -c < \"$1\"", 5).run(String::from("Cargo.toml"), &mut log).unwrap();
+        assert_eq!(output.answer, fs::read("Cargo.toml").unwrap().len().to_string());
+        assert_eq!(log, "working\n");
+    }
+
+    #[test]
+    fn timeout_kills_what_a_wrapper_started() {
+        // The grandchild keeps stdout open long after the timeout.
+        let started = Instant::now();
+        let mut log = String::new();
+        let error = solver("sleep 30 & sleep 30; echo 1", 1).run(String::from("Cargo.toml"), &mut log).unwrap_err();
+
+        assert_eq!(error.to_string(), "Timed out after 1 seconds");
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn background_processes_holding_the_pipes_time_out() {
+        // The command itself exits, but what it left behind keeps the pipes open.
+        let started = Instant::now();
+        let mut log = String::new();
+        let error = solver("(sleep 30; echo late) & echo 1", 1).run(String::from("Cargo.toml"), &mut log).unwrap_err();
+
+        assert_eq!(error.to_string(), "Timed out after 1 seconds");
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::team::is_user_name;
+use crate::rookrustpad::service::aoc::AocAnswer;
+
+pub const DEFAULT_HISTORY_DIRECTORY: &str = "data/aoc_history";
+
+/// A history file is rewritten with only the runs in use once it grows past this.
+pub const DEFAULT_COMPACT_SIZE: u64 = 1 << 20;
+
+/// How much of a run's log is kept, solvers can log a lot.
+const LOG_LIMIT: usize = 4096;
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct AocRunKey {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub user: Option<String>,
+    pub dataset: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocRun {
+    /// Milliseconds since the epoch when the run finished.
+    pub finished_at: u64,
+    pub answer: AocAnswer,
+}
+
+impl AocRun {
+    fn key(&self) -> AocRunKey {
+        AocRunKey {
+            year: self.answer.year,
+            day: self.answer.day,
+            part: self.answer.part,
+            user: self.answer.user.clone(),
+            dataset: self.answer.dataset.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AocRunIndex {
+    latest: HashMap<AocRunKey, AocRun>,
+    /// The latest run with the dataset's own parameters.
+    latest_default: HashMap<AocRunKey, AocRun>,
+    /// The size of each history file when it was last compacted.
+    compacted_sizes: HashMap<PathBuf, u64>,
+}
+
+impl AocRunIndex {
+    fn insert(&mut self, run: AocRun) {
+        if run.answer.default_params {
+            self.latest_default.insert(run.key(), run.clone());
+        }
+        self.latest.insert(run.key(), run);
+    }
+
+    /// The runs still in use, oldest first, which is all a history file needs to keep.
+    fn runs(&self) -> Vec<&AocRun> {
+        // A latest run with default parameters is also the latest default one. The older default
+        // runs go first so they stay behind a latest run finished in the same millisecond.
+        let mut runs: Vec<&AocRun> = self.latest_default
+            .iter()
+            .filter(|(key, _)| !self.latest.get(key).is_some_and(|latest| latest.answer.default_params))
+            .map(|(_, run)| run)
+            .collect();
+        runs.extend(self.latest.values());
+        runs.sort_by_key(|run| run.finished_at);
+        runs
+    }
+
+    /// Rewrites a history file with the runs still in use for its user.
+    fn compact(&mut self, path: &Path, user: &Option<String>) -> Result<(), Box<dyn Error>> {
+        let mut compacted = String::new();
+        for run in self.runs().into_iter().filter(|run| &run.answer.user == user) {
+            compacted.push_str(&serde_json::to_string(run)?);
+            compacted.push('\n');
+        }
+        fs::write(path, &compacted)?;
+        self.compacted_sizes.insert(path.to_path_buf(), compacted.len() as u64);
+        Ok(())
+    }
+}
+
+/// Remembers the latest run of each problem and dataset, for each user.
+/// Runs are appended to a jsonl file per user in the directory, shared.jsonl for the shared
+/// inputs and users/<user>.jsonl for everyone else's, and read back on first use.
+#[derive(Debug)]
+pub struct AocRunHistory {
+    pub directory: String,
+    /// Size past which a history file is compacted as runs are recorded.
+    pub compact_size: u64,
+    /// None until the files are read.
+    index: Mutex<Option<AocRunIndex>>,
+}
+
+impl AocRunHistory {
+    pub fn new(directory: &str) -> AocRunHistory {
+        AocRunHistory { directory: directory.to_string(), compact_size: DEFAULT_COMPACT_SIZE, index: Mutex::new(None) }
+    }
+
+    /// The file a user's runs go to, None for names that can't be a file name.
+    fn file_for(&self, user: &Option<String>) -> Option<PathBuf> {
+        match user {
+            None => Some(Path::new(&self.directory).join("shared.jsonl")),
+            Some(user) if is_user_name(user) => Some(Path::new(&self.directory).join("users").join(format!("{}.jsonl", user))),
+            Some(_) => None,
+        }
+    }
+
+    fn history_files(&self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let mut files = vec![Path::new(&self.directory).join("shared.jsonl")];
+        match fs::read_dir(Path::new(&self.directory).join("users")) {
+            Ok(entries) => {
+                for entry in entries {
+                    let path = entry?.path();
+                    if path.extension().is_some_and(|e| e == "jsonl") {
+                        files.push(path);
+                    }
+                }
+            },
+            Err(error) if error.kind() == ErrorKind::NotFound => {},
+            Err(error) => return Err(error.into()),
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Reads one history file, rewriting it with only the runs still in use when it has older ones.
+    fn load_file(path: &Path, index: &mut AocRunIndex) -> Result<(), Box<dyn Error>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut file_index = AocRunIndex::default();
+        let mut lines = 0;
+        for (n, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            lines += 1;
+            match serde_json::from_str::<AocRun>(line) {
+                Ok(run) => file_index.insert(run),
+                Err(error) => println!("Skipping an invalid run at {}:{}: {}", path.display(), n + 1, error),
+            }
+        }
+
+        let runs = file_index.runs();
+        for run in &runs {
+            index.insert((*run).clone());
+        }
+        if runs.len() < lines {
+            let user = runs.first().and_then(|run| run.answer.user.clone());
+            file_index.compact(path, &user)?;
+        }
+        index.compacted_sizes.insert(path.to_path_buf(), fs::metadata(path)?.len());
+        Ok(())
+    }
+
+    fn load(&self) -> AocRunIndex {
+        let mut index = AocRunIndex::default();
+        let files = self.history_files().unwrap_or_else(|error| {
+            println!("Unable to list the runs in {}: {}", self.directory, error);
+            vec![]
+        });
+        for path in files {
+            if let Err(error) = AocRunHistory::load_file(&path, &mut index) {
+                println!("Unable to read the runs in {}: {}", path.display(), error);
+            }
+        }
+        index
+    }
+
+    fn with_index<T>(&self, f: impl FnOnce(&mut AocRunIndex) -> T) -> Option<T> {
+        let mut index = self.index.lock().ok()?;
+        Some(f(index.get_or_insert_with(|| self.load())))
+    }
+
+    /// Adds a run to a history file, compacting the file when it has grown past compact_size
+    /// and to twice its size after the last compaction.
+    fn append(&self, path: &Path, run: &AocRun, index: &mut AocRunIndex) -> Result<(), Box<dyn Error>> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(run)?)?;
+
+        let len = file.metadata()?.len();
+        let compacted_size = index.compacted_sizes.get(path).copied().unwrap_or(0);
+        if len > self.compact_size && len > 2 * compacted_size {
+            index.compact(path, &run.answer.user)?;
+        }
+        Ok(())
+    }
+
+    pub fn record(&self, answer: &AocAnswer) {
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut answer = answer.clone();
+        answer.log = truncate_log(&answer.log);
+        let run = AocRun { finished_at, answer };
+        let path = self.file_for(&run.answer.user);
+
+        self.with_index(|index| {
+            index.insert(run.clone());
+            if let Some(path) = path {
+                if let Err(error) = self.append(&path, &run, index) {
+                    println!("Unable to save the run to {}: {}", path.display(), error);
+                }
+            }
+        });
+    }
+
+    pub fn latest(&self, year: u32, day: u32, part: u8, user: &Option<String>, dataset: &Option<String>) -> Option<AocRun> {
+        let key = AocRunKey { year, day, part, user: user.clone(), dataset: dataset.clone() };
+        self.with_index(|index| index.latest.get(&key).cloned())?
+    }
+
+    /// The latest run on someone's main input with its own parameters, what the calendar's star shows.
+    pub fn latest_default(&self, year: u32, day: u32, part: u8, user: &Option<String>) -> Option<AocRun> {
+        let key = AocRunKey { year, day, part, user: user.clone(), dataset: None };
+        self.with_index(|index| index.latest_default.get(&key).cloned())?
+    }
+}
+
+/// The start of a log, the history keeps every run's so it is cut short.
+fn truncate_log(log: &str) -> String {
+    if log.len() <= LOG_LIMIT {
+        return log.to_string();
+    }
+    let mut end = LOG_LIMIT;
+    while !log.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n[{} more bytes of log not kept]\n", &log[..end], log.len() - end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::params::AocParams;
+
+    fn answer(user: Option<&str>, result: &str, default_params: bool) -> AocAnswer {
+        AocAnswer {
+            year: 2022,
+            day: 11,
+            part: 1,
+            user: user.map(String::from),
+            dataset: None,
+            variant: None,
+            params: AocParams::default(),
+            result: Ok(result.to_string()),
+            artifacts: vec![],
+            expected: None,
+            default_params,
+            log: String::new(),
+            execution_time: 0.5,
+        }
+    }
+
+    fn result(run: Option<AocRun>) -> Option<String> {
+        run.and_then(|run| run.answer.result.ok())
+    }
+
+    #[test]
+    fn keeps_runs_for_each_user_across_restarts() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path().to_string_lossy().into_owned();
+        let history = AocRunHistory::new(&directory);
+        history.record(&answer(None, "10", true));
+        history.record(&answer(Some("alice"), "20", true));
+        history.record(&answer(Some("alice"), "21", false));
+
+        let history = AocRunHistory::new(&directory);
+        let alice = Some(String::from("alice"));
+        assert_eq!(result(history.latest(2022, 11, 1, &None, &None)), Some(String::from("10")));
+        assert_eq!(result(history.latest(2022, 11, 1, &alice, &None)), Some(String::from("21")));
+        assert_eq!(result(history.latest_default(2022, 11, 1, &alice)), Some(String::from("20")));
+        assert!(Path::new(&directory).join("shared.jsonl").is_file());
+        assert!(Path::new(&directory).join("users/alice.jsonl").is_file());
+    }
+
+    #[test]
+    fn drops_runs_that_were_replaced_when_reading() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path().to_string_lossy().into_owned();
+        let history = AocRunHistory::new(&directory);
+        for result in ["1", "2", "3"] {
+            history.record(&answer(None, result, true));
+        }
+        history.record(&answer(None, "4", false));
+
+        let history = AocRunHistory::new(&directory);
+        assert_eq!(result(history.latest(2022, 11, 1, &None, &None)), Some(String::from("4")));
+        assert_eq!(result(history.latest_default(2022, 11, 1, &None)), Some(String::from("3")));
+        let contents = fs::read_to_string(Path::new(&directory).join("shared.jsonl")).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    #[test]
+    fn skips_invalid_lines() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path().to_string_lossy().into_owned();
+        AocRunHistory::new(&directory).record(&answer(None, "10", true));
+        let path = Path::new(&directory).join("shared.jsonl");
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("not json\n{}", contents)).unwrap();
+
+        let history = AocRunHistory::new(&directory);
+        assert_eq!(result(history.latest(2022, 11, 1, &None, &None)), Some(String::from("10")));
+    }
+
+    #[test]
+    fn compacts_files_that_grow_past_the_limit() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path().to_string_lossy().into_owned();
+        let mut history = AocRunHistory::new(&directory);
+        history.compact_size = 2000;
+        let path = Path::new(&directory).join("shared.jsonl");
+        let mut longest = 0;
+        for n in 0..50 {
+            history.record(&answer(None, &n.to_string(), true));
+            longest = longest.max(fs::read_to_string(&path).unwrap().lines().count());
+        }
+
+        assert!(longest < 50, "{} runs in the file", longest);
+        assert!(fs::metadata(&path).unwrap().len() <= 4000);
+        let history = AocRunHistory::new(&directory);
+        assert_eq!(result(history.latest(2022, 11, 1, &None, &None)), Some(String::from("49")));
+    }
+
+    #[test]
+    fn keeps_only_the_start_of_long_logs() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path().to_string_lossy().into_owned();
+        let mut long = answer(None, "10", true);
+        long.log = "é".repeat(LOG_LIMIT);
+        AocRunHistory::new(&directory).record(&long);
+
+        let run = AocRunHistory::new(&directory).latest(2022, 11, 1, &None, &None).unwrap();
+        assert!(run.answer.log.starts_with("éé"));
+        assert!(run.answer.log.ends_with(&format!("[{} more bytes of log not kept]\n", LOG_LIMIT)));
+    }
+}
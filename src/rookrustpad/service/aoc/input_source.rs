@@ -0,0 +1,227 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+pub mod archive;
+pub mod directory;
+pub mod embedded;
+
+use archive::ArchiveInputSource;
+use directory::DirectoryInputSource;
+use embedded::EmbeddedInputSource;
+
+/// Somewhere inputs and their sidecars can be read from.
+/// Files are named by their path in the input directory with "/" separators,
+/// for example 2022/day_11_1.test.txt, whatever the source stores them as.
+pub trait AocInputSource: fmt::Debug + Send + Sync {
+    /// Where the files come from, for listings and logs.
+    fn describe(&self) -> String;
+
+    fn list_files(&self) -> Result<Vec<String>, Box<dyn Error>>;
+
+    /// A file's contents, None when this source doesn't have it.
+    fn read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>>;
+
+    /// The file on disk when it can be handed to a solver as it is.
+    fn local_path(&self, _name: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Where a file is, as shown to people.
+    fn location(&self, name: &str) -> String {
+        format!("{}!{}", self.describe(), name)
+    }
+
+    fn has_file(&self, name: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.list_files()?.iter().any(|f| f == name))
+    }
+
+    /// When a file last changed and its size, None for files that don't change while the app runs.
+    fn stamp(&self, _name: &str) -> Option<(SystemTime, u64)> {
+        None
+    }
+
+    fn is_writable(&self) -> bool {
+        false
+    }
+
+    /// Saves a file, only called when is_writable.
+    fn write_file(&self, name: &str, _contents: &[u8]) -> Result<(), Box<dyn Error>> {
+        Err(format!("Unable to write {}, {} is read only", name, self.describe()).into())
+    }
+}
+
+/// How an input source is set up in the settings.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AocInputSourceSettings {
+    /// A directory of year directories, files may be compressed with gzip or zstd.
+    Directory { path: String },
+    /// A zip or tar file. When year is set its files are that year's, otherwise
+    /// they are under year directories like the input directory.
+    Archive { path: String, year: Option<u32> },
+    /// The example inputs built into the binary.
+    Embedded,
+}
+
+impl AocInputSourceSettings {
+    pub fn create(&self) -> Box<dyn AocInputSource> {
+        match self {
+            AocInputSourceSettings::Directory { path } => Box::new(DirectoryInputSource::new(path)),
+            AocInputSourceSettings::Archive { path, year } => Box::new(ArchiveInputSource::new(path, *year)),
+            AocInputSourceSettings::Embedded => Box::new(EmbeddedInputSource::examples()),
+        }
+    }
+}
+
+/// Input sources tried in order, the first one with a file is the one it is read from.
+#[derive(Debug, Default)]
+pub struct AocInputSources {
+    pub layers: Vec<Box<dyn AocInputSource>>,
+}
+
+impl AocInputSources {
+    pub fn new(layers: Vec<Box<dyn AocInputSource>>) -> AocInputSources {
+        AocInputSources { layers }
+    }
+
+    /// Lists every layer once, for looking up many files without asking the layers each time.
+    pub fn index(&self) -> Result<AocInputIndex<'_>, Box<dyn Error>> {
+        let mut layers = vec![];
+        for layer in &self.layers {
+            layers.push((layer.as_ref(), layer.list_files()?.into_iter().collect()));
+        }
+        Ok(AocInputIndex { layers })
+    }
+
+    /// The layer a file is read from.
+    pub fn find(&self, name: &str) -> Result<Option<&dyn AocInputSource>, Box<dyn Error>> {
+        for layer in &self.layers {
+            if layer.has_file(name)? {
+                return Ok(Some(layer.as_ref()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The layer a file is read from when saved copies come first: the writable layers,
+    /// where write_file puts files, then the others in order.
+    pub fn find_writable_first(&self, name: &str) -> Result<Option<&dyn AocInputSource>, Box<dyn Error>> {
+        let (writable, read_only): (Vec<_>, Vec<_>) = self.layers.iter().partition(|layer| layer.is_writable());
+        for layer in writable.into_iter().chain(read_only) {
+            if layer.has_file(name)? {
+                return Ok(Some(layer.as_ref()));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        match self.find(name)? {
+            Some(layer) => layer.read_file(name),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves a file to the first layer that can be written to, returning where it went.
+    pub fn write_file(&self, name: &str, contents: &[u8]) -> Result<String, Box<dyn Error>> {
+        let layer = self.layers
+            .iter()
+            .find(|layer| layer.is_writable())
+            .ok_or("None of the input sources can be written to")?;
+        layer.write_file(name, contents)?;
+        Ok(layer.location(name))
+    }
+
+    /// Puts an input where a solver can open it: the file itself when it is a plain
+    /// file on disk, otherwise a temporary copy that is removed once it is dropped.
+    pub fn materialize(&self, name: &str) -> Result<AocMaterializedInput, Box<dyn Error>> {
+        let layer = self.find(name)?.ok_or_else(|| format!("Input {} not found", name))?;
+        if let Some(path) = layer.local_path(name) {
+            return Ok(AocMaterializedInput::Local(path));
+        }
+
+        let contents = layer.read_file(name)?.ok_or_else(|| format!("Input {} not found", name))?;
+        let mut file = tempfile::Builder::new()
+            .prefix("rookrustpad-")
+            .suffix(".txt")
+            .tempfile()?;
+        file.write_all(&contents)?;
+        file.flush()?;
+        Ok(AocMaterializedInput::Temporary(file))
+    }
+}
+
+/// The file names of every layer as listed by AocInputSources::index.
+#[derive(Debug)]
+pub struct AocInputIndex<'a> {
+    layers: Vec<(&'a dyn AocInputSource, BTreeSet<String>)>,
+}
+
+impl<'a> AocInputIndex<'a> {
+    /// Every file name in any layer, once, sorted.
+    pub fn files(&self) -> Vec<String> {
+        let files: BTreeSet<&String> = self.layers.iter().flat_map(|(_, files)| files).collect();
+        files.into_iter().cloned().collect()
+    }
+
+    /// Like AocInputSources::find.
+    pub fn find(&self, name: &str) -> Option<&'a dyn AocInputSource> {
+        self.layers.iter().find(|(_, files)| files.contains(name)).map(|(layer, _)| *layer)
+    }
+
+    /// Like AocInputSources::find_writable_first.
+    pub fn find_writable_first(&self, name: &str) -> Option<&'a dyn AocInputSource> {
+        let (writable, read_only): (Vec<_>, Vec<_>) = self.layers.iter().partition(|(layer, _)| layer.is_writable());
+        writable.into_iter().chain(read_only).find(|(_, files)| files.contains(name)).map(|(layer, _)| *layer)
+    }
+}
+
+/// An input file a solver can open, see AocInputSources::materialize.
+#[derive(Debug)]
+pub enum AocMaterializedInput {
+    Local(PathBuf),
+    Temporary(NamedTempFile),
+}
+
+impl AocMaterializedInput {
+    pub fn path(&self) -> String {
+        match self {
+            AocMaterializedInput::Local(path) => path.to_string_lossy().into_owned(),
+            AocMaterializedInput::Temporary(file) => file.path().to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// Splits the compression off a stored file's name: day_11_1.txt.gz is day_11_1.txt with gzip.
+pub fn compressed_name(stored_name: &str) -> (&str, Option<&'static str>) {
+    for extension in [".gz", ".zst"] {
+        if let Some(name) = stored_name.strip_suffix(extension) {
+            return (name, Some(extension));
+        }
+    }
+    (stored_name, None)
+}
+
+/// Undoes the compression named by compressed_name.
+pub fn decompress(contents: Vec<u8>, compression: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut decompressed = vec![];
+    match compression {
+        None => return Ok(contents),
+        Some(".gz") => {
+            GzDecoder::new(contents.as_slice()).read_to_end(&mut decompressed)?;
+        },
+        Some(".zst") => {
+            zstd::stream::read::Decoder::new(contents.as_slice())?.read_to_end(&mut decompressed)?;
+        },
+        Some(other) => return Err(format!("Unknown compression {}", other).into()),
+    }
+    Ok(decompressed)
+}
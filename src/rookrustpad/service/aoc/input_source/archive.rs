@@ -0,0 +1,229 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind, Read};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use flate2::read::GzDecoder;
+
+use crate::rookrustpad::service::aoc::input_source::{compressed_name, decompress, AocInputSource};
+
+/// Inputs kept in a zip or tar file (.zip, .tar, .tar.gz, .tgz or .tar.zst), usually one per year.
+/// The file names are indexed on first use and again whenever the archive changes on disk,
+/// only reading a file goes through the archive.
+#[derive(Debug)]
+pub struct ArchiveInputSource {
+    pub path: String,
+    /// When set the archive's files are this year's, day_11_1.txt is 2022/day_11_1.txt.
+    pub year: Option<u32>,
+    index: Mutex<Option<ArchiveIndex>>,
+}
+
+/// The archive's file names as of its modification time and size, no time when it is missing.
+#[derive(Debug)]
+struct ArchiveIndex {
+    modified: Option<SystemTime>,
+    len: u64,
+    files: Vec<String>,
+}
+
+type EntryVisitor<'a> = dyn FnMut(&str, Option<&'static str>, &mut dyn Read) -> Result<bool, Box<dyn Error>> + 'a;
+
+impl ArchiveInputSource {
+    pub fn new(path: &str, year: Option<u32>) -> ArchiveInputSource {
+        ArchiveInputSource { path: path.to_string(), year, index: Mutex::new(None) }
+    }
+
+    /// The sorted file names, listing the archive again only when it changed since the last time.
+    /// A missing archive has no files, so the other sources still work until it is put back.
+    fn indexed_files(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let (modified, len) = match fs::metadata(&self.path) {
+            Ok(metadata) => (Some(metadata.modified()?), metadata.len()),
+            Err(e) if e.kind() == ErrorKind::NotFound => (None, 0),
+            Err(e) => return Err(format!("Unable to open {}: {}", self.path, e).into()),
+        };
+
+        let mut index = self.index.lock().map_err(|_| format!("The index of {} is unavailable", self.path))?;
+        if let Some(index) = index.as_ref().filter(|index| index.modified == modified && index.len == len) {
+            return Ok(index.files.clone());
+        }
+
+        let mut files = vec![];
+        if modified.is_some() {
+            self.visit_entries(&mut |name, _, _| {
+                files.push(name.to_string());
+                Ok(false)
+            })?;
+        }
+        else {
+            println!("Input archive {} is missing, it has no inputs", self.path);
+        }
+        files.sort();
+        files.dedup();
+        *index = Some(ArchiveIndex { modified, len, files: files.clone() });
+        Ok(files)
+    }
+
+    /// The name an entry is listed under and its compression, None for directories.
+    fn entry_name(&self, stored_name: &str) -> Option<(String, Option<&'static str>)> {
+        let stored_name = stored_name.trim_start_matches("./");
+        if stored_name.is_empty() || stored_name.ends_with('/') {
+            return None;
+        }
+
+        let (name, compression) = compressed_name(stored_name);
+        let name = match self.year {
+            Some(year) => format!("{}/{}", year, name),
+            None => name.to_string(),
+        };
+        Some((name, compression))
+    }
+
+    /// Calls visit with every file in the archive until it returns true.
+    fn visit_entries(&self, visit: &mut EntryVisitor) -> Result<(), Box<dyn Error>> {
+        let file = BufReader::new(File::open(&self.path)
+            .map_err(|e| format!("Unable to open {}: {}", self.path, e))?);
+
+        if self.path.ends_with(".zip") {
+            let mut archive = zip::ZipArchive::new(file)?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i)?;
+                if let Some((name, compression)) = self.entry_name(entry.name()) {
+                    if visit(&name, compression, &mut entry)? {
+                        break;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let reader: Box<dyn Read> = if self.path.ends_with(".tar.gz") || self.path.ends_with(".tgz") {
+            Box::new(GzDecoder::new(file))
+        }
+        else if self.path.ends_with(".tar.zst") {
+            Box::new(zstd::stream::read::Decoder::new(file)?)
+        }
+        else if self.path.ends_with(".tar") {
+            Box::new(file)
+        }
+        else {
+            return Err(format!("Unknown archive type: {}", self.path).into());
+        };
+
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let stored_name = entry.path()?.to_string_lossy().into_owned();
+            if let Some((name, compression)) = self.entry_name(&stored_name) {
+                if visit(&name, compression, &mut entry)? {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AocInputSource for ArchiveInputSource {
+    fn describe(&self) -> String {
+        self.path.clone()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        self.indexed_files()
+    }
+
+    fn has_file(&self, name: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.indexed_files()?.binary_search_by(|f| f.as_str().cmp(name)).is_ok())
+    }
+
+    /// The archive's own, its files change with it.
+    fn stamp(&self, name: &str) -> Option<(SystemTime, u64)> {
+        let index = self.index.lock().ok()?;
+        let index = index.as_ref().filter(|index| index.files.binary_search_by(|f| f.as_str().cmp(name)).is_ok())?;
+        Some((index.modified?, index.len))
+    }
+
+    fn read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        if !self.has_file(name)? {
+            return Ok(None);
+        }
+
+        let mut contents = None;
+        self.visit_entries(&mut |entry_name, compression, entry| {
+            if entry_name != name {
+                return Ok(false);
+            }
+            let mut bytes = vec![];
+            entry.read_to_end(&mut bytes)?;
+            contents = Some(decompress(bytes, compression)?);
+            Ok(true)
+        })?;
+        Ok(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn write_tar(path: &std::path::Path, files: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *contents).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn lists_and_reads_files_under_the_year() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("2022.tar");
+        write_tar(&path, &[("day_1_1.txt", b"1\n2\n"), ("./day_2_1.test.txt", b"A Y\n")]);
+        let source = ArchiveInputSource::new(&path.to_string_lossy(), Some(2022));
+
+        assert_eq!(source.list_files().unwrap(), vec!["2022/day_1_1.txt", "2022/day_2_1.test.txt"]);
+        assert!(source.has_file("2022/day_2_1.test.txt").unwrap());
+        assert!(!source.has_file("day_2_1.test.txt").unwrap());
+        assert_eq!(source.read_file("2022/day_1_1.txt").unwrap(), Some(b"1\n2\n".to_vec()));
+        assert_eq!(source.read_file("2022/day_3_1.txt").unwrap(), None);
+    }
+
+    #[test]
+    fn indexes_again_when_the_archive_changes() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("inputs.tar");
+        write_tar(&path, &[("2022/day_1_1.txt", b"1\n")]);
+        let source = ArchiveInputSource::new(&path.to_string_lossy(), None);
+        assert_eq!(source.list_files().unwrap(), vec!["2022/day_1_1.txt"]);
+
+        write_tar(&path, &[("2022/day_1_1.txt", b"1\n"), ("2022/day_2_1.txt", b"2\n")]);
+        let modified = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+
+        assert!(source.has_file("2022/day_2_1.txt").unwrap());
+        assert_eq!(source.read_file("2022/day_2_1.txt").unwrap(), Some(b"2\n".to_vec()));
+    }
+
+    #[test]
+    fn treats_missing_archives_as_empty() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("inputs.tar");
+        let source = ArchiveInputSource::new(&path.to_string_lossy(), None);
+        assert!(source.list_files().unwrap().is_empty());
+        assert!(!source.has_file("2022/day_1_1.txt").unwrap());
+        assert_eq!(source.read_file("2022/day_1_1.txt").unwrap(), None);
+
+        write_tar(&path, &[("2022/day_1_1.txt", b"1\n")]);
+        assert_eq!(source.list_files().unwrap(), vec!["2022/day_1_1.txt"]);
+    }
+}
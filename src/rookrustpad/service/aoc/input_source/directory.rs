@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::fs::{self, read_dir};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::rookrustpad::service::aoc::input_source::{compressed_name, decompress, AocInputSource};
+
+/// Files under a directory, the way the inputs have always been kept. A file can
+/// be compressed, day_11_1.txt.gz or day_11_1.txt.zst is read as day_11_1.txt,
+/// and a plain file wins over a compressed one.
+#[derive(Debug)]
+pub struct DirectoryInputSource {
+    pub directory: PathBuf,
+}
+
+impl DirectoryInputSource {
+    pub fn new(directory: &str) -> DirectoryInputSource {
+        DirectoryInputSource { directory: PathBuf::from(directory) }
+    }
+
+    /// The file a name is stored in and its compression.
+    fn stored_file(&self, name: &str) -> Option<(PathBuf, Option<&'static str>)> {
+        [None, Some(".gz"), Some(".zst")].into_iter().find_map(|compression| {
+            let path = self.directory.join(format!("{}{}", name, compression.unwrap_or("")));
+            path.is_file().then_some((path, compression))
+        })
+    }
+
+    fn walk(&self, directory: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+        for dir_ent in read_dir(directory)? {
+            let dir_ent = dir_ent?;
+            let file_name = dir_ent.file_name();
+            let file_name = file_name.to_str().ok_or("Invalid file name")?;
+            let stored_name = format!("{}{}", prefix, file_name);
+
+            if dir_ent.file_type()?.is_dir() {
+                self.walk(&dir_ent.path(), &format!("{}/", stored_name), files)?;
+            }
+            else {
+                files.push(compressed_name(&stored_name).0.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AocInputSource for DirectoryInputSource {
+    fn describe(&self) -> String {
+        self.directory.display().to_string()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut files = vec![];
+        if self.directory.is_dir() {
+            self.walk(&self.directory, "", &mut files)?;
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    fn read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        match self.stored_file(name) {
+            Some((path, compression)) => Ok(Some(decompress(fs::read(path)?, compression)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn local_path(&self, name: &str) -> Option<PathBuf> {
+        match self.stored_file(name)? {
+            (path, None) => Some(path),
+            _ => None,
+        }
+    }
+
+    fn location(&self, name: &str) -> String {
+        let path = self.stored_file(name).map(|(path, _)| path).unwrap_or_else(|| self.directory.join(name));
+        path.display().to_string()
+    }
+
+    fn has_file(&self, name: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.stored_file(name).is_some())
+    }
+
+    fn stamp(&self, name: &str) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(self.stored_file(name)?.0).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    fn is_writable(&self) -> bool {
+        true
+    }
+
+    fn write_file(&self, name: &str, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+        let path = self.directory.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+
+use crate::rookrustpad::service::aoc::input_source::AocInputSource;
+
+/// Embeds a file from www/aoc_input under its name there.
+macro_rules! embed_input {
+    ($name:literal) => {
+        ($name, include_bytes!(concat!("../../../../../www/aoc_input/", $name)) as &'static [u8])
+    };
+}
+
+/// The puzzle examples, so the binary can check its solvers without an input directory.
+pub static EXAMPLE_INPUTS: &[(&str, &[u8])] = &[
+    embed_input!("2022/day_11_1.test.txt"),
+    embed_input!("2022/day_11_1.test.toml"),
+    embed_input!("2022/day_11_2.test.txt"),
+    embed_input!("2022/day_11_2.test.toml"),
+    embed_input!("2022/day_11_2.rounds.txt"),
+    embed_input!("2022/day_11_2.rounds.toml"),
+];
+
+/// Files built into the binary.
+#[derive(Debug)]
+pub struct EmbeddedInputSource {
+    pub files: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedInputSource {
+    pub fn examples() -> EmbeddedInputSource {
+        EmbeddedInputSource { files: EXAMPLE_INPUTS }
+    }
+}
+
+impl AocInputSource for EmbeddedInputSource {
+    fn describe(&self) -> String {
+        String::from("embedded")
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(self.files.iter().map(|(name, _)| name.to_string()).collect())
+    }
+
+    fn read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        Ok(self.files.iter().find(|(n, _)| *n == name).map(|(_, contents)| contents.to_vec()))
+    }
+}
@@ -0,0 +1,213 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::AocService;
+
+/// AoC asks that a private leaderboard is fetched at most once every 15 minutes.
+const LEADERBOARD_REFRESH: Duration = Duration::from_secs(15 * 60);
+
+/// When a star was earned, in seconds since the epoch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocStarTime {
+    pub get_star_ts: i64,
+    #[serde(default)]
+    pub star_index: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocLeaderboardMember {
+    pub id: u64,
+    /// None for anonymous users.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    #[serde(default)]
+    pub global_score: u32,
+    #[serde(default)]
+    pub last_star_ts: i64,
+    /// Stars by day then part.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, AocStarTime>>,
+}
+
+impl AocLeaderboardMember {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    pub fn star_time(&self, day: u32, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+            .map(|star| star.get_star_ts)
+    }
+}
+
+/// A private leaderboard as AoC's json API gives it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocLeaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    pub members: BTreeMap<String, AocLeaderboardMember>,
+}
+
+impl AocLeaderboard {
+    pub fn parse(contents: &str, year: u32) -> Result<AocLeaderboard, Box<dyn Error>> {
+        let leaderboard: AocLeaderboard = serde_json::from_str(contents)
+            .map_err(|e| format!("Not a private leaderboard: {}", e))?;
+        if leaderboard.event != year.to_string() {
+            return Err(format!("The leaderboard is for {}, not {}", leaderboard.event, year).into());
+        }
+        Ok(leaderboard)
+    }
+
+    /// Members by local score, then stars, then who got their last star first.
+    pub fn ranked(&self) -> Vec<&AocLeaderboardMember> {
+        let mut members: Vec<&AocLeaderboardMember> = self.members.values().collect();
+        members.sort_by_key(|m| (Reverse(m.local_score), Reverse(m.stars), m.last_star_ts));
+        members
+    }
+
+    /// The days anyone has a star for.
+    pub fn days(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self.members
+            .values()
+            .flat_map(|m| m.completion_day_level.keys().filter_map(|day| day.parse().ok()))
+            .collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+}
+
+impl AocService {
+    pub fn get_leaderboard_path(&self, year: u32) -> String {
+        format!("{}/{}.json", self.client.settings.leaderboard_directory, year)
+    }
+
+    pub fn load_leaderboard(&self, year: u32) -> Result<Option<AocLeaderboard>, Box<dyn Error>> {
+        let path = self.get_leaderboard_path(year);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)?;
+        Ok(Some(AocLeaderboard::parse(&contents, year).map_err(|e| format!("{}: {}", path, e))?))
+    }
+
+    /// Checks and keeps a leaderboard's json, replacing the one saved for the year.
+    pub fn import_leaderboard(&self, year: u32, contents: &str) -> Result<AocLeaderboard, Box<dyn Error>> {
+        let leaderboard = AocLeaderboard::parse(contents, year)?;
+
+        let path = self.get_leaderboard_path(year);
+        if let Some(directory) = Path::new(&path).parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&path, contents)?;
+        Ok(leaderboard)
+    }
+
+    /// Fetches the configured leaderboard unless the saved one is recent enough.
+    pub fn fetch_leaderboard(&self, year: u32) -> Result<AocLeaderboard, Box<dyn Error>> {
+        let id = self.client.settings.leaderboard_id
+            .as_ref()
+            .ok_or("No leaderboard, set leaderboard_id under [aoc]")?;
+
+        let age = fs::metadata(self.get_leaderboard_path(year))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+        if let Some(age) = age.filter(|age| *age < LEADERBOARD_REFRESH) {
+            return Err(format!(
+                "The leaderboard was fetched {} minutes ago, AoC asks for at most one fetch every {} minutes",
+                age.as_secs() / 60, LEADERBOARD_REFRESH.as_secs() / 60).into());
+        }
+
+        let contents = self.client.get(&format!("/{}/leaderboard/private/view/{}.json", year, id))?;
+        self.import_leaderboard(year, &contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEADERBOARD: &str = r#"{
+        "owner_id": 1,
+        "event": "2022",
+        "members": {
+            "1": {
+                "id": 1, "name": "Ada", "stars": 3, "local_score": 10, "last_star_ts": 300,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 100 }, "2": { "get_star_ts": 200 } },
+                    "11": { "1": { "get_star_ts": 300 } }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 3, "local_score": 10, "last_star_ts": 250,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 110 }, "2": { "get_star_ts": 250 } },
+                    "2": { "1": { "get_star_ts": 240 } }
+                }
+            },
+            "3": {
+                "id": 3, "name": "Brian", "stars": 4, "local_score": 10, "last_star_ts": 400,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 120 }, "2": { "get_star_ts": 130 } },
+                    "2": { "1": { "get_star_ts": 350 }, "2": { "get_star_ts": 400 } }
+                }
+            },
+            "4": { "id": 4, "name": "Carol", "stars": 0, "local_score": 0 }
+        }
+    }"#;
+
+    #[test]
+    fn ranks_by_score_then_stars_then_last_star() {
+        let leaderboard = AocLeaderboard::parse(LEADERBOARD, 2022).unwrap();
+
+        let ranked: Vec<u64> = leaderboard.ranked().iter().map(|m| m.id).collect();
+        assert_eq!(ranked, vec![3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn names_anonymous_members_by_id() {
+        let leaderboard = AocLeaderboard::parse(LEADERBOARD, 2022).unwrap();
+
+        assert_eq!(leaderboard.members["1"].display_name(), "Ada");
+        assert_eq!(leaderboard.members["2"].display_name(), "(anonymous user #2)");
+    }
+
+    #[test]
+    fn lists_the_days_with_stars() {
+        let leaderboard = AocLeaderboard::parse(LEADERBOARD, 2022).unwrap();
+
+        assert_eq!(leaderboard.days(), vec![1, 2, 11]);
+    }
+
+    #[test]
+    fn finds_star_times() {
+        let leaderboard = AocLeaderboard::parse(LEADERBOARD, 2022).unwrap();
+        let ada = &leaderboard.members["1"];
+
+        assert_eq!(ada.star_time(1, 2), Some(200));
+        assert_eq!(ada.star_time(11, 1), Some(300));
+        assert_eq!(ada.star_time(11, 2), None);
+        assert_eq!(ada.star_time(2, 1), None);
+    }
+
+    #[test]
+    fn rejects_a_leaderboard_from_another_year() {
+        let error = AocLeaderboard::parse(LEADERBOARD, 2023).unwrap_err();
+        assert_eq!(error.to_string(), "The leaderboard is for 2022, not 2023");
+
+        assert!(AocLeaderboard::parse("{}", 2022).unwrap_err().to_string().starts_with("Not a private leaderboard"));
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::rookrustpad::service::aoc::params::AocParamInput;
+
+/// What is known about a dataset, kept in a sidecar next to its input: day_11_1.test.txt
+/// is described by day_11_1.test.toml.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AocInputMetadata {
+    pub description: Option<String>,
+    /// Where the input came from, for example "example from statement".
+    pub source: Option<String>,
+    /// Expected answers for this dataset by part number, numbers don't need quotes.
+    #[serde(deserialize_with = "deserialize_strings", skip_serializing_if = "BTreeMap::is_empty")]
+    pub expected: BTreeMap<String, String>,
+    /// Solver parameters to use with this dataset, requests can still override them.
+    #[serde(deserialize_with = "deserialize_strings", skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: AocParamInput,
+}
+
+/// Reads a table of any TOML values as text, so 1 = 10197 reads the same as 1 = "10197".
+fn deserialize_strings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error> {
+    let values: BTreeMap<String, toml::Value> = BTreeMap::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .map(|(name, value)| match value {
+            toml::Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect())
+}
+
+impl AocInputMetadata {
+    /// The sidecar's name for an input's name.
+    pub fn sidecar_name(input_name: &str) -> String {
+        let stem = input_name.strip_suffix(".txt").unwrap_or(input_name);
+        format!("{}.toml", stem)
+    }
+
+    /// Parses a sidecar, location is only used in errors.
+    pub fn parse(contents: &str, location: &str) -> Result<AocInputMetadata, Box<dyn Error>> {
+        Ok(toml::from_str(contents).map_err(|e| format!("Invalid sidecar {}: {}", location, e))?)
+    }
+
+    pub fn expected_answer(&self, part: u8) -> Option<&String> {
+        self.expected.get(&part.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_quoted_and_unquoted_answers() {
+        let metadata = AocInputMetadata::parse("[expected]\n1 = 10197\n2 = \"abc\"\n", "day_1_1.toml").unwrap();
+
+        assert_eq!(metadata.expected_answer(1), Some(&String::from("10197")));
+        assert_eq!(metadata.expected_answer(2), Some(&String::from("abc")));
+        assert_eq!(metadata.expected_answer(3), None);
+    }
+
+    #[test]
+    fn reads_params_of_any_type_as_text() {
+        let metadata = AocInputMetadata::parse("source = \"example\"\n[params]\nrounds = 20\nfast = true\n", "day_1_1.toml").unwrap();
+
+        assert_eq!(metadata.source.as_deref(), Some("example"));
+        assert_eq!(metadata.params.get("rounds").map(String::as_str), Some("20"));
+        assert_eq!(metadata.params.get("fast").map(String::as_str), Some("true"));
+    }
+
+    #[test]
+    fn writes_answers_back_quoted() {
+        let mut metadata = AocInputMetadata::default();
+        metadata.expected.insert(String::from("1"), String::from("10197"));
+
+        let contents = toml::to_string(&metadata).unwrap();
+        assert_eq!(AocInputMetadata::parse(&contents, "day_1_1.toml").unwrap(), metadata);
+        assert!(contents.contains("1 = \"10197\""), "{}", contents);
+    }
+
+    #[test]
+    fn names_the_sidecar_in_errors() {
+        let error = AocInputMetadata::parse("[expected]\n1 = [", "inputs/day_1_1.toml").unwrap_err();
+        assert!(error.to_string().starts_with("Invalid sidecar inputs/day_1_1.toml: "), "{}", error);
+    }
+}
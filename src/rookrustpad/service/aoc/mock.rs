@@ -0,0 +1,283 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use lazy_regex::regex;
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::status::{unlock_time, DAYS_PER_YEAR};
+
+/// A stand-in for the Advent of Code website, the [mock_aoc] table of the settings.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct AocMockSettings {
+    pub port: u16,
+    /// Holds {year}/day_{day}.input.txt, {year}/day_{day}.html, {year}/answers.toml
+    /// and {year}/leaderboard_{id}.json.
+    pub fixtures: String,
+    /// Saved statement pages, served when a day has no page in the fixtures.
+    pub statement_directory: String,
+    /// The time the mock's clock starts at, like "2022-12-11T04:59:50Z", the real time when not set.
+    pub now: Option<String>,
+    /// Requests a session may make in a minute before getting 429s.
+    pub requests_per_minute: usize,
+    /// Seconds before another answer after a wrong one.
+    pub wrong_answer_wait: u64,
+    /// Wrong answers to a part after which the wait grows to five minutes.
+    pub wrong_answers_before_long_wait: u32,
+}
+
+impl Default for AocMockSettings {
+    fn default() -> Self {
+        AocMockSettings {
+            port: 9099,
+            fixtures: String::from("fixtures/aoc"),
+            statement_directory: String::from("www/aoc_problems"),
+            now: None,
+            requests_per_minute: 30,
+            wrong_answer_wait: 60,
+            wrong_answers_before_long_wait: 4,
+        }
+    }
+}
+
+/// What the mock sends back, the handlers only pass it on.
+#[derive(Debug, Clone)]
+pub struct AocMockResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl AocMockResponse {
+    fn text(status: u16, body: impl Into<String>) -> AocMockResponse {
+        AocMockResponse { status, content_type: "text/plain; charset=utf-8", body: body.into() }
+    }
+
+    fn html(status: u16, body: impl Into<String>) -> AocMockResponse {
+        AocMockResponse { status, content_type: "text/html; charset=utf-8", body: body.into() }
+    }
+
+    fn json(body: String) -> AocMockResponse {
+        AocMockResponse { status: 200, content_type: "application/json", body }
+    }
+
+    /// An answer's reply, a page with the message in its article like the real one.
+    fn answer_page(year: u32, day: u32, message: &str) -> AocMockResponse {
+        AocMockResponse::html(200, format!(
+            "<!DOCTYPE html>\n<html lang=\"en-us\">\n<head><title>Day {day} - Advent of Code {year}</title></head>\n\
+             <body>\n<main>\n<article><p>{message}</p></article>\n</main>\n</body>\n</html>\n"))
+    }
+}
+
+/// A wait the way AoC writes it in a cooldown, "4m 32s" or "34s".
+fn format_wait(seconds: u64) -> String {
+    if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    }
+    else {
+        format!("{}s", seconds)
+    }
+}
+
+/// How a wrong answer's reply asks for the wait, in whole minutes when it is some.
+fn wait_text(seconds: u64) -> String {
+    match seconds {
+        60 => String::from("Please wait one minute before trying again."),
+        seconds if seconds > 0 && seconds % 60 == 0 => format!("Please wait {} minutes before trying again.", seconds / 60),
+        seconds => format!("You have {} left to wait.", format_wait(seconds)),
+    }
+}
+
+/// What the mock remembers about someone logged in.
+#[derive(Debug, Default)]
+struct AocMockSession {
+    requests: VecDeque<Instant>,
+    cooldown_until: Option<Instant>,
+    wrong_answers: HashMap<(u32, u32, u8), u32>,
+    solved: BTreeSet<(u32, u32, u8)>,
+}
+
+#[derive(Debug)]
+pub struct AocMock {
+    pub settings: AocMockSettings,
+    started: Instant,
+    start_time: DateTime<Utc>,
+    sessions: Mutex<HashMap<String, AocMockSession>>,
+}
+
+impl AocMock {
+    pub fn new(settings: AocMockSettings) -> Result<AocMock, String> {
+        let start_time = match &settings.now {
+            Some(now) => DateTime::parse_from_rfc3339(now)
+                .map_err(|e| format!("Invalid mock time {}: {}", now, e))?
+                .with_timezone(&Utc),
+            None => Utc::now(),
+        };
+        Ok(AocMock { settings, started: Instant::now(), start_time, sessions: Mutex::new(HashMap::new()) })
+    }
+
+    /// The mock's clock, it runs from the configured start time.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.start_time + chrono::Duration::from_std(self.started.elapsed()).unwrap_or_default()
+    }
+
+    fn is_unlocked(&self, year: u32, day: u32) -> bool {
+        (1..=DAYS_PER_YEAR).contains(&day) && unlock_time(year, day).is_some_and(|unlock| self.now() >= unlock)
+    }
+
+    fn fixture(&self, year: u32, name: &str) -> Option<String> {
+        fs::read_to_string(format!("{}/{}/{}", self.settings.fixtures, year, name)).ok()
+    }
+
+    fn answers(&self, year: u32) -> BTreeMap<String, BTreeMap<String, String>> {
+        self.fixture(year, "answers.toml")
+            .and_then(|answers| toml::from_str(&answers).map_err(|e| println!("Invalid answers for {}: {}", year, e)).ok())
+            .unwrap_or_default()
+    }
+
+    /// Runs f with the session's state after counting the request against the rate limit.
+    fn with_session<F>(&self, session: Option<&str>, f: F) -> AocMockResponse
+        where F: FnOnce(&mut AocMockSession) -> AocMockResponse
+    {
+        let Some(session) = session.filter(|session| !session.is_empty()) else {
+            return AocMockResponse::text(400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n");
+        };
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return AocMockResponse::text(500, "The mock is unavailable\n");
+        };
+
+        let state = sessions.entry(session.to_string()).or_default();
+        let now = Instant::now();
+        while state.requests.front().is_some_and(|at| now.duration_since(*at) > Duration::from_secs(60)) {
+            state.requests.pop_front();
+        }
+        if state.requests.len() >= self.settings.requests_per_minute {
+            return AocMockResponse::text(429, "Too many requests, slow down.\n");
+        }
+        state.requests.push_back(now);
+
+        f(state)
+    }
+
+    pub fn input(&self, session: Option<&str>, year: u32, day: u32) -> AocMockResponse {
+        self.with_session(session, |_| {
+            if !self.is_unlocked(year, day) {
+                return AocMockResponse::text(404, "Please don't repeatedly request this endpoint before it unlocks! \
+                    The calendar countdown is synchronized with the server time; the link will be enabled on the calendar \
+                    the instant this puzzle becomes available.\n");
+            }
+            match self.fixture(year, &format!("day_{}.input.txt", day)) {
+                Some(input) => AocMockResponse::text(200, input),
+                None => AocMockResponse::text(404, format!("No input fixture for {} day {}\n", year, day)),
+            }
+        })
+    }
+
+    /// The puzzle page, part two's description only shows once part one is solved.
+    pub fn puzzle(&self, session: Option<&str>, year: u32, day: u32) -> AocMockResponse {
+        if !self.is_unlocked(year, day) {
+            return AocMockResponse::text(404, "404 Not Found\n");
+        }
+
+        let page = self.fixture(year, &format!("day_{}.html", day)).or_else(|| {
+            fs::read_to_string(format!("{}/{}/Day {} - Advent of Code {}.html", self.settings.statement_directory, year, day, year)).ok()
+        });
+        let Some(page) = page else {
+            return AocMockResponse::text(404, format!("No puzzle page for {} day {}\n", year, day));
+        };
+
+        let solved_first = session
+            .and_then(|session| self.sessions.lock().ok()?.get(session).map(|s| s.solved.contains(&(year, day, 1))))
+            .unwrap_or(false);
+        if solved_first {
+            return AocMockResponse::html(200, page);
+        }
+
+        let articles: Vec<_> = regex!(r#"(?s)<article class="day-desc">.*?</article>"#).find_iter(&page).collect();
+        match articles.get(1) {
+            Some(second) => AocMockResponse::html(200, format!("{}{}", &page[..second.start()], &page[second.end()..])),
+            None => AocMockResponse::html(200, page),
+        }
+    }
+
+    /// Checks an answer the way AoC does: cooldowns first, then the level, then the value.
+    pub fn answer(&self, session: Option<&str>, year: u32, day: u32, level: u8, answer: &str) -> AocMockResponse {
+        if !self.is_unlocked(year, day) {
+            return AocMockResponse::text(404, "404 Not Found\n");
+        }
+        let expected = self.answers(year).get(&day.to_string()).and_then(|parts| parts.get(&level.to_string()).cloned());
+        let return_link = format!("[<a href=\"/{}/day/{}\">Return to Day {}</a>]", year, day, day);
+
+        self.with_session(session, |state| {
+            let now = Instant::now();
+            if let Some(until) = state.cooldown_until.filter(|until| *until > now) {
+                let left = (until - now).as_secs() + 1;
+                return AocMockResponse::answer_page(year, day, &format!(
+                    "You gave an answer too recently; you have to wait after submitting an answer before trying again.  \
+                     You have {} left to wait. {}", format_wait(left), return_link));
+            }
+
+            let right_level = !state.solved.contains(&(year, day, level)) && (level == 1 || state.solved.contains(&(year, day, 1)));
+            let Some(expected) = expected.filter(|_| right_level && (1..=2).contains(&level)) else {
+                return AocMockResponse::answer_page(year, day, &format!(
+                    "You don't seem to be solving the right level.  Did you already complete it? {}", return_link));
+            };
+
+            let answer = answer.trim();
+            if answer == expected {
+                state.solved.insert((year, day, level));
+                return AocMockResponse::answer_page(year, day, &format!(
+                    "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to finishing. \
+                     [<a href=\"/{}/day/{}#part2\">Continue to Part Two</a>]", year, day));
+            }
+
+            let wrong = state.wrong_answers.entry((year, day, level)).or_default();
+            *wrong += 1;
+            let (wait, wait_text) = if *wrong >= self.settings.wrong_answers_before_long_wait {
+                (300, format!("Because you have guessed incorrectly {} times on this puzzle, please wait 5 minutes before trying again.", wrong))
+            }
+            else {
+                (self.settings.wrong_answer_wait, wait_text(self.settings.wrong_answer_wait))
+            };
+            state.cooldown_until = Some(now + Duration::from_secs(wait));
+
+            let hint = match (answer.parse::<i128>(), expected.parse::<i128>()) {
+                (Ok(answer), Ok(expected)) if answer > expected => "; your answer is too high",
+                (Ok(answer), Ok(expected)) if answer < expected => "; your answer is too low",
+                _ => "",
+            };
+            AocMockResponse::answer_page(year, day, &format!(
+                "That's not the right answer{}.  If you're stuck, make sure you're using the full input data. {} {}",
+                hint, wait_text, return_link))
+        })
+    }
+
+    pub fn leaderboard(&self, session: Option<&str>, year: u32, id: &str) -> AocMockResponse {
+        self.with_session(session, |_| {
+            if !regex!(r"^\d+$").is_match(id) {
+                return AocMockResponse::text(404, "404 Not Found\n");
+            }
+            match self.fixture(year, &format!("leaderboard_{}.json", id)) {
+                Some(leaderboard) => AocMockResponse::json(leaderboard),
+                None => AocMockResponse::text(404, format!("No leaderboard fixture {} for {}\n", id, year)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asks_for_the_configured_wait() {
+        assert_eq!(wait_text(60), "Please wait one minute before trying again.");
+        assert_eq!(wait_text(120), "Please wait 2 minutes before trying again.");
+        assert_eq!(wait_text(90), "You have 1m 30s left to wait.");
+        assert_eq!(wait_text(30), "You have 30s left to wait.");
+        assert_eq!(wait_text(0), "You have 0s left to wait.");
+    }
+}
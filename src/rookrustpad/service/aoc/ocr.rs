@@ -0,0 +1,223 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::Serialize;
+
+/// The letters AoC draws 6 rows high, about 4 columns wide.
+const FONT_6: &[(char, &[&str])] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The letters AoC draws 10 rows high, 6 columns wide.
+const FONT_10: &[(char, &[&str])] = &[
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// A glyph that matched no letter, as it was drawn.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AocUnknownGlyph {
+    /// Which glyph it was, counting from 0 on the left.
+    pub position: usize,
+    pub rows: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AocOcrError {
+    /// Nothing is lit.
+    Empty,
+    /// The lit rows are not 6 or 10 high.
+    UnknownHeight(usize),
+    /// What could be read, with ? for each unknown glyph.
+    UnknownGlyphs { text: String, glyphs: Vec<AocUnknownGlyph> },
+}
+
+impl fmt::Display for AocOcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocOcrError::Empty => write!(f, "The drawing is empty."),
+            AocOcrError::UnknownHeight(height) =>
+                write!(f, "The letters are {} rows high, only 6 and 10 are known.", height),
+            AocOcrError::UnknownGlyphs { text, glyphs } => {
+                writeln!(f, "Read {} but {} glyph(s) are unknown:", text, glyphs.len())?;
+                for glyph in glyphs {
+                    writeln!(f, "glyph {}:", glyph.position)?;
+                    for row in &glyph.rows {
+                        writeln!(f, "{}", row)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for AocOcrError {}
+
+fn is_lit(c: char) -> bool {
+    !matches!(c, '.' | ' ' | '\t')
+}
+
+/// Reads the letters in a drawing. Any character but `.` and blanks counts as lit,
+/// so `#` and `█` both work. Letters are split on columns with nothing lit.
+pub fn read_letters(drawing: &str) -> Result<String, AocOcrError> {
+    let mut rows: Vec<Vec<bool>> = drawing
+        .lines()
+        .map(|line| line.trim_end().chars().map(is_lit).collect())
+        .collect();
+
+    // Drop blank rows above and below the letters.
+    while rows.last().is_some_and(|row| !row.contains(&true)) {
+        rows.pop();
+    }
+    let first = rows.iter().position(|row| row.contains(&true)).ok_or(AocOcrError::Empty)?;
+    rows.drain(..first);
+
+    let font = match rows.len() {
+        6 => FONT_6,
+        10 => FONT_10,
+        height => return Err(AocOcrError::UnknownHeight(height)),
+    };
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut text = String::new();
+    let mut unknown: Vec<AocUnknownGlyph> = vec![];
+    let mut x = 0;
+
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+
+        let glyph: Vec<String> = rows
+            .iter()
+            .map(|row| (start..x).map(|i| if row.get(i) == Some(&true) { '#' } else { '.' }).collect())
+            .collect();
+
+        match font.iter().find(|(_, shape)| shape.len() == glyph.len() && shape.iter().zip(&glyph).all(|(a, b)| *a == b)) {
+            Some((letter, _)) => text.push(*letter),
+            None => {
+                text.push('?');
+                unknown.push(AocUnknownGlyph {
+                    position: text.chars().count() - 1,
+                    rows: glyph,
+                });
+            }
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(text)
+    }
+    else {
+        Err(AocOcrError::UnknownGlyphs { text, glyphs: unknown })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws a word from a font with a blank column between the letters.
+    fn draw(font: &[(char, &[&str])], word: &str) -> String {
+        let shapes: Vec<&[&str]> = word
+            .chars()
+            .map(|c| font.iter().find(|(letter, _)| *letter == c).expect("letter in the font").1)
+            .collect();
+        (0..shapes[0].len())
+            .map(|row| shapes.iter().map(|shape| shape[row]).collect::<Vec<_>>().join("."))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn reads_a_six_row_drawing() {
+        let drawing = "\
+████ █  █ ███  █    
+█    █  █ █  █ █    
+███  ████ ███  █    
+█    █  █ █  █ █    
+█    █  █ █  █ █    
+████ █  █ ███  ████ ";
+        assert_eq!(read_letters(drawing), Ok(String::from("EHBL")));
+    }
+
+    #[test]
+    fn reads_every_letter_of_both_fonts() {
+        for font in [FONT_6, FONT_10] {
+            let word: String = font.iter().map(|(letter, _)| *letter).collect();
+            assert_eq!(read_letters(&draw(font, &word)), Ok(word));
+        }
+    }
+
+    #[test]
+    fn ignores_blank_rows_around_the_letters() {
+        let drawing = format!("\n......\n{}\n\n", draw(FONT_10, "NXZ"));
+        assert_eq!(read_letters(&drawing), Ok(String::from("NXZ")));
+    }
+
+    #[test]
+    fn rejects_empty_drawings_and_unknown_heights() {
+        assert_eq!(read_letters("....\n    \n"), Err(AocOcrError::Empty));
+        assert_eq!(read_letters("#\n#\n#"), Err(AocOcrError::UnknownHeight(3)));
+    }
+
+    #[test]
+    fn reports_unknown_glyphs_with_what_was_read() {
+        let unknown = ["##", "##", "..", "..", "##", "##"];
+        let drawing: String = draw(FONT_6, "AB")
+            .lines()
+            .zip(unknown)
+            .map(|(letters, glyph)| format!("{}.{}", letters, glyph))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let error = read_letters(&drawing).unwrap_err();
+        assert_eq!(error, AocOcrError::UnknownGlyphs {
+            text: String::from("AB?"),
+            glyphs: vec![AocUnknownGlyph {
+                position: 2,
+                rows: unknown.iter().map(|row| row.to_string()).collect(),
+            }],
+        });
+        assert!(error.to_string().starts_with("Read AB? but 1 glyph(s) are unknown:\nglyph 2:\n##\n##\n"));
+    }
+}
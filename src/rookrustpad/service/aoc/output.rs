@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::ocr::{read_letters, AocOcrError};
+
+/// Something a solver found along the way that is worth showing next to the answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AocArtifact {
+    /// Key/value rows, for example a count per monkey.
+    Table { title: String, rows: Vec<(String, String)> },
+    /// A 2D character grid, one string per row.
+    Grid { title: String, rows: Vec<String> },
+    /// (x, y) points drawn as a line chart.
+    Series { title: String, points: Vec<(f64, f64)> },
+    /// An SVG document shown inline, the solver is trusted to produce it.
+    Svg { title: String, svg: String },
+}
+
+impl AocArtifact {
+    pub fn table<K: ToString, V: ToString>(title: &str, rows: impl IntoIterator<Item = (K, V)>) -> AocArtifact {
+        AocArtifact::Table {
+            title: title.to_string(),
+            rows: rows.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    pub fn grid<R: ToString>(title: &str, rows: impl IntoIterator<Item = R>) -> AocArtifact {
+        AocArtifact::Grid {
+            title: title.to_string(),
+            rows: rows.into_iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    pub fn series(title: &str, points: Vec<(f64, f64)>) -> AocArtifact {
+        AocArtifact::Series { title: title.to_string(), points }
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            AocArtifact::Table { title, .. }
+            | AocArtifact::Grid { title, .. }
+            | AocArtifact::Series { title, .. }
+            | AocArtifact::Svg { title, .. } => title,
+        }
+    }
+}
+
+/// What a solver returns: the answer plus any artifacts.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct AocOutput {
+    pub answer: String,
+    pub artifacts: Vec<AocArtifact>,
+}
+
+impl AocOutput {
+    pub fn new<A: ToString>(answer: A) -> AocOutput {
+        AocOutput {
+            answer: answer.to_string(),
+            artifacts: vec![],
+        }
+    }
+
+    /// Reads the block letters in a drawing for the answer, keeping the drawing as a grid.
+    pub fn from_letters(drawing: &str) -> Result<AocOutput, AocOcrError> {
+        let letters = read_letters(drawing)?;
+        Ok(AocOutput::new(letters).with(AocArtifact::grid("Drawing", drawing.lines())))
+    }
+
+    pub fn with(mut self, artifact: AocArtifact) -> AocOutput {
+        self.artifacts.push(artifact);
+        self
+    }
+}
+
+impl From<String> for AocOutput {
+    fn from(answer: String) -> AocOutput {
+        AocOutput::new(answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serializes_artifacts_by_kind() {
+        let output = AocOutput::new(10605)
+            .with(AocArtifact::table("Inspections", [("Monkey 0", 101), ("Monkey 1", 95)]))
+            .with(AocArtifact::series("Worry", vec![(1.0, 2.5)]));
+
+        assert_eq!(serde_json::to_value(&output).unwrap(), json!({
+            "answer": "10605",
+            "artifacts": [
+                { "kind": "table", "title": "Inspections", "rows": [["Monkey 0", "101"], ["Monkey 1", "95"]] },
+                { "kind": "series", "title": "Worry", "points": [[1.0, 2.5]] },
+            ],
+        }));
+    }
+
+    #[test]
+    fn reads_back_serialized_artifacts() {
+        let grid = AocArtifact::grid("Drawing", ["#..", ".#."]);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(json, r##"{"kind":"grid","title":"Drawing","rows":["#..",".#."]}"##);
+        assert_eq!(serde_json::from_str::<AocArtifact>(&json).unwrap(), grid);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Parameters as given, by name, before they are checked against what a solver declares.
+pub type AocParamInput = BTreeMap<String, String>;
+
+/// The type of a parameter with its default, and bounds for numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AocParamKind {
+    Integer { default: i64, min: i64, max: i64 },
+    Float { default: f64, min: f64, max: f64 },
+    Bool { default: bool },
+    Text { default: &'static str },
+}
+
+/// A parameter a solver accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AocParam {
+    pub name: &'static str,
+    pub help: &'static str,
+    #[serde(flatten)]
+    pub kind: AocParamKind,
+}
+
+impl AocParam {
+    pub const fn integer(name: &'static str, help: &'static str, default: i64, min: i64, max: i64) -> AocParam {
+        AocParam { name, help, kind: AocParamKind::Integer { default, min, max } }
+    }
+
+    pub fn default_value(&self) -> AocParamValue {
+        match self.kind {
+            AocParamKind::Integer { default, .. } => AocParamValue::Integer(default),
+            AocParamKind::Float { default, .. } => AocParamValue::Float(default),
+            AocParamKind::Bool { default } => AocParamValue::Bool(default),
+            AocParamKind::Text { default } => AocParamValue::Text(default.to_string()),
+        }
+    }
+
+    /// Parses and checks a value given for this parameter.
+    pub fn parse(&self, value: &str) -> Result<AocParamValue, AocParamError> {
+        let invalid = |reason: String| AocParamError::Invalid {
+            name: self.name.to_string(),
+            value: value.to_string(),
+            reason,
+        };
+
+        match self.kind {
+            AocParamKind::Integer { min, max, .. } => {
+                let n: i64 = value.trim().parse().map_err(|_| invalid(String::from("not an integer")))?;
+                if n < min || n > max {
+                    return Err(invalid(format!("must be from {} to {}", min, max)));
+                }
+                Ok(AocParamValue::Integer(n))
+            },
+            AocParamKind::Float { min, max, .. } => {
+                let n: f64 = value.trim().parse().map_err(|_| invalid(String::from("not a number")))?;
+                if !(n >= min && n <= max) {
+                    return Err(invalid(format!("must be from {} to {}", min, max)));
+                }
+                Ok(AocParamValue::Float(n))
+            },
+            AocParamKind::Bool { .. } => match value.trim() {
+                "true" | "on" | "1" => Ok(AocParamValue::Bool(true)),
+                "false" | "off" | "0" => Ok(AocParamValue::Bool(false)),
+                _ => Err(invalid(String::from("not true or false"))),
+            },
+            AocParamKind::Text { .. } => Ok(AocParamValue::Text(value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AocParamValue {
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl fmt::Display for AocParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocParamValue::Integer(n) => write!(f, "{}", n),
+            AocParamValue::Float(n) => write!(f, "{}", n),
+            AocParamValue::Bool(b) => write!(f, "{}", b),
+            AocParamValue::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AocParamError {
+    Unknown { name: String },
+    Invalid { name: String, value: String, reason: String },
+    Missing { name: String },
+}
+
+impl fmt::Display for AocParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocParamError::Unknown { name } => write!(f, "Unknown parameter: {}", name),
+            AocParamError::Invalid { name, value, reason } =>
+                write!(f, "Invalid value {:?} for {}: {}", value, name, reason),
+            AocParamError::Missing { name } => write!(f, "Missing parameter: {}", name),
+        }
+    }
+}
+
+impl Error for AocParamError {}
+
+/// The effective parameters for a run: every declared parameter, given or defaulted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AocParams(pub BTreeMap<String, AocParamValue>);
+
+impl AocParams {
+    /// Checks the given parameters against the declared ones and fills in defaults.
+    pub fn resolve(declared: &[AocParam], given: &AocParamInput) -> Result<AocParams, AocParamError> {
+        if let Some(name) = given.keys().find(|name| !declared.iter().any(|p| p.name == name.as_str())) {
+            return Err(AocParamError::Unknown { name: name.clone() });
+        }
+
+        let mut values = BTreeMap::new();
+        for param in declared {
+            let value = match given.get(param.name) {
+                Some(value) => param.parse(value)?,
+                None => param.default_value(),
+            };
+            values.insert(param.name.to_string(), value);
+        }
+
+        Ok(AocParams(values))
+    }
+
+    /// True when the given parameters come to the same values as a dataset's own.
+    /// Parameters that don't resolve are never the dataset's.
+    pub fn are_defaults(declared: &[AocParam], given: &AocParamInput, dataset: &AocParamInput) -> bool {
+        match (AocParams::resolve(declared, given), AocParams::resolve(declared, dataset)) {
+            (Ok(given), Ok(dataset)) => given == dataset,
+            _ => false,
+        }
+    }
+
+    pub fn value(&self, name: &str) -> Result<&AocParamValue, AocParamError> {
+        self.0.get(name).ok_or_else(|| AocParamError::Missing { name: name.to_string() })
+    }
+
+    pub fn integer(&self, name: &str) -> Result<i64, AocParamError> {
+        match self.value(name)? {
+            AocParamValue::Integer(n) => Ok(*n),
+            other => Err(AocParamError::Invalid {
+                name: name.to_string(),
+                value: other.to_string(),
+                reason: String::from("not an integer"),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECLARED: &[AocParam] = &[
+        AocParam::integer("rounds", "How many rounds to play", 20, 1, 10_000),
+        AocParam { name: "scale", help: "A factor", kind: AocParamKind::Float { default: 1.5, min: 0.0, max: 10.0 } },
+        AocParam { name: "verbose", help: "Log more", kind: AocParamKind::Bool { default: false } },
+        AocParam { name: "mode", help: "How to run", kind: AocParamKind::Text { default: "fast" } },
+    ];
+
+    fn input(pairs: &[(&str, &str)]) -> AocParamInput {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn fills_in_defaults() {
+        let params = AocParams::resolve(DECLARED, &AocParamInput::new()).unwrap();
+        assert_eq!(params.integer("rounds"), Ok(20));
+        assert_eq!(params.value("scale"), Ok(&AocParamValue::Float(1.5)));
+        assert_eq!(params.value("verbose"), Ok(&AocParamValue::Bool(false)));
+        assert_eq!(params.value("mode"), Ok(&AocParamValue::Text(String::from("fast"))));
+    }
+
+    #[test]
+    fn coerces_values_to_their_types() {
+        let params = AocParams::resolve(DECLARED, &input(&[("rounds", " 100 "), ("scale", "2"), ("verbose", "on"), ("mode", "slow")])).unwrap();
+        assert_eq!(params.integer("rounds"), Ok(100));
+        assert_eq!(params.value("scale"), Ok(&AocParamValue::Float(2.0)));
+        assert_eq!(params.value("verbose"), Ok(&AocParamValue::Bool(true)));
+        assert_eq!(params.value("mode"), Ok(&AocParamValue::Text(String::from("slow"))));
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        for (name, value, reason) in [("rounds", "2.5", "not an integer"), ("scale", "big", "not a number"), ("verbose", "yes", "not true or false")] {
+            assert_eq!(AocParams::resolve(DECLARED, &input(&[(name, value)])), Err(AocParamError::Invalid {
+                name: name.to_string(),
+                value: value.to_string(),
+                reason: reason.to_string(),
+            }));
+        }
+    }
+
+    #[test]
+    fn checks_bounds() {
+        assert!(AocParams::resolve(DECLARED, &input(&[("rounds", "1"), ("scale", "10")])).is_ok());
+        assert_eq!(AocParams::resolve(DECLARED, &input(&[("rounds", "0")])), Err(AocParamError::Invalid {
+            name: String::from("rounds"),
+            value: String::from("0"),
+            reason: String::from("must be from 1 to 10000"),
+        }));
+        assert!(AocParams::resolve(DECLARED, &input(&[("scale", "-0.1")])).is_err());
+        assert!(AocParams::resolve(DECLARED, &input(&[("scale", "NaN")])).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_params() {
+        assert_eq!(
+            AocParams::resolve(DECLARED, &input(&[("rounds", "5"), ("speed", "3")])),
+            Err(AocParamError::Unknown { name: String::from("speed") }));
+        assert_eq!(AocParams::resolve(DECLARED, &AocParamInput::new()).unwrap().integer("speed"),
+            Err(AocParamError::Missing { name: String::from("speed") }));
+    }
+
+    #[test]
+    fn tells_when_params_are_the_datasets_own() {
+        let dataset = input(&[("rounds", "5")]);
+        assert!(AocParams::are_defaults(DECLARED, &dataset, &dataset));
+        // Spelled differently but the same value, or a default given explicitly.
+        assert!(AocParams::are_defaults(DECLARED, &input(&[("rounds", "05"), ("mode", "fast")]), &dataset));
+        assert!(!AocParams::are_defaults(DECLARED, &input(&[("rounds", "6")]), &dataset));
+        assert!(!AocParams::are_defaults(DECLARED, &AocParamInput::new(), &dataset));
+        assert!(AocParams::are_defaults(DECLARED, &AocParamInput::new(), &AocParamInput::new()));
+        // Nothing invalid counts as the defaults, even when both sides are invalid the same way.
+        assert!(!AocParams::are_defaults(DECLARED, &input(&[("speed", "3")]), &input(&[("speed", "3")])));
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fs::{self, read_dir};
+use std::sync::Mutex;
+use std::path::Path;
+use std::time::SystemTime;
+
+use lazy_regex::regex;
+use serde::Serialize;
+
+use crate::rookrustpad::service::aoc::statement::html_text;
+use crate::rookrustpad::service::aoc::AocService;
+
+/// Characters shown on each side of the first match.
+const SNIPPET_CONTEXT: usize = 80;
+
+/// Lines of notes for each year and day.
+type AocDayNotes = BTreeMap<(u32, u32), Vec<String>>;
+
+/// Every indexed file with when it changed and its size, a search rebuilds the index when it differs.
+type AocSearchFingerprint = BTreeMap<String, Option<(SystemTime, u64)>>;
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+pub enum AocSearchKind {
+    Statement,
+    Source,
+    Notes,
+}
+
+/// A word of a document, its lowercase form and where it is in the text.
+#[derive(Debug, Clone)]
+struct AocSearchWord {
+    term: String,
+    start: usize,
+    end: usize,
+}
+
+/// A day's statement or notes, or a solver's source file, as plain text.
+#[derive(Debug, Clone)]
+struct AocSearchDocument {
+    kind: AocSearchKind,
+    year: u32,
+    day: u32,
+    title: String,
+    text: String,
+    words: Vec<AocSearchWord>,
+}
+
+/// Part of a snippet, the highlighted parts are what matched.
+#[derive(Debug, Clone, Serialize)]
+pub struct AocSearchSnippet {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocSearchHit {
+    pub kind: AocSearchKind,
+    pub year: u32,
+    pub day: u32,
+    pub title: String,
+    /// The line of the first match, counting from 1.
+    pub line: usize,
+    /// How many times the query's words and phrases are in the document.
+    pub matches: usize,
+    pub snippet: Vec<AocSearchSnippet>,
+}
+
+/// An inverted index of every searchable document.
+#[derive(Debug)]
+struct AocSearchIndex {
+    fingerprint: AocSearchFingerprint,
+    documents: Vec<AocSearchDocument>,
+    /// Each term's documents and word positions.
+    postings: HashMap<String, Vec<(usize, usize)>>,
+}
+
+/// The search index, built on the first search and again once the indexed files
+/// changed or it was invalidated by something the app wrote.
+#[derive(Debug, Default)]
+pub struct AocSearch {
+    index: Mutex<Option<AocSearchIndex>>,
+}
+
+impl AocSearch {
+    pub fn new() -> AocSearch {
+        AocSearch::default()
+    }
+
+    /// Has the next search build the index again.
+    pub fn invalidate(&self) {
+        if let Ok(mut index) = self.index.lock() {
+            *index = None;
+        }
+    }
+}
+
+/// Letters and digits, so identifiers like parse_monkey split into their words.
+fn words(text: &str) -> Vec<AocSearchWord> {
+    regex!(r"[^\W_]+")
+        .find_iter(text)
+        .map(|m| AocSearchWord { term: m.as_str().to_lowercase(), start: m.start(), end: m.end() })
+        .collect()
+}
+
+/// A query's words and "quoted phrases", a document has to have every one of them.
+fn parse_query(query: &str) -> Vec<Vec<String>> {
+    regex!(r#""([^"]*)"?|(\S+)"#)
+        .captures_iter(query)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .map(|m| words(m.as_str()).into_iter().map(|w| w.term).collect::<Vec<String>>())
+        .filter(|phrase| !phrase.is_empty())
+        .collect()
+}
+
+/// Whitespace is folded so code and wrapped text read as one line.
+fn snippet_text(text: &str) -> String {
+    regex!(r"\s+").replace_all(text, " ").to_string()
+}
+
+impl AocSearchDocument {
+    fn new(kind: AocSearchKind, year: u32, day: u32, title: String, text: String) -> AocSearchDocument {
+        let words = words(&text);
+        AocSearchDocument { kind, year, day, title, text, words }
+    }
+
+    /// The text around the first match, with every match in it highlighted.
+    fn snippet(&self, ranges: &[(usize, usize)]) -> Vec<AocSearchSnippet> {
+        let Some(&(first_start, first_end)) = ranges.first() else {
+            return vec![];
+        };
+
+        let mut start = first_start.saturating_sub(SNIPPET_CONTEXT);
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+        // Starts on a whole word.
+        if let Some(space) = self.text[start..first_start].find(char::is_whitespace).filter(|_| start > 0) {
+            start += space + 1;
+        }
+        let mut end = (first_end + SNIPPET_CONTEXT).min(self.text.len());
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+
+        let mut snippet = vec![];
+        let mut plain = if start > 0 { String::from("…") } else { String::new() };
+        let mut position = start;
+        for &(match_start, match_end) in ranges.iter().filter(|(s, e)| *s >= start && *e <= end) {
+            if match_start < position {
+                continue;
+            }
+            plain.push_str(&self.text[position..match_start]);
+            snippet.push(AocSearchSnippet { text: snippet_text(&plain), highlighted: false });
+            snippet.push(AocSearchSnippet { text: snippet_text(&self.text[match_start..match_end]), highlighted: true });
+            plain = String::new();
+            position = match_end;
+        }
+        plain.push_str(&self.text[position..end]);
+        if end < self.text.len() {
+            plain.push('…');
+        }
+        snippet.push(AocSearchSnippet { text: snippet_text(&plain), highlighted: false });
+
+        snippet
+    }
+}
+
+impl AocSearchIndex {
+    fn new(fingerprint: AocSearchFingerprint, documents: Vec<AocSearchDocument>) -> AocSearchIndex {
+        let mut postings: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (n, document) in documents.iter().enumerate() {
+            for (position, word) in document.words.iter().enumerate() {
+                postings.entry(word.term.clone()).or_default().push((n, position));
+            }
+        }
+        AocSearchIndex { fingerprint, documents, postings }
+    }
+
+    /// Where a phrase starts in each document it is in, its first word's postings
+    /// are checked for the rest of the phrase following it.
+    fn find_phrase(&self, phrase: &[String]) -> HashMap<usize, Vec<usize>> {
+        let mut found: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &(n, position) in self.postings.get(&phrase[0]).into_iter().flatten() {
+            let words = &self.documents[n].words;
+            let follows = phrase
+                .iter()
+                .enumerate()
+                .skip(1)
+                .all(|(i, term)| words.get(position + i).is_some_and(|w| &w.term == term));
+            if follows {
+                found.entry(n).or_default().push(position);
+            }
+        }
+        found
+    }
+
+    /// Documents with every word and phrase of the query, those with the most matches first.
+    fn search(&self, query: &str, limit: usize) -> Vec<AocSearchHit> {
+        let phrases = parse_query(query);
+        if phrases.is_empty() {
+            return vec![];
+        }
+        let found: Vec<HashMap<usize, Vec<usize>>> = phrases.iter().map(|phrase| self.find_phrase(phrase)).collect();
+
+        let mut hits = vec![];
+        for (n, document) in self.documents.iter().enumerate() {
+            if !found.iter().all(|f| f.contains_key(&n)) {
+                continue;
+            }
+
+            let mut ranges: Vec<(usize, usize)> = phrases
+                .iter()
+                .zip(&found)
+                .flat_map(|(phrase, f)| f[&n].iter().map(|&p| (document.words[p].start, document.words[p + phrase.len() - 1].end)))
+                .collect();
+            ranges.sort();
+
+            hits.push(AocSearchHit {
+                kind: document.kind,
+                year: document.year,
+                day: document.day,
+                title: document.title.clone(),
+                line: document.text[..ranges[0].0].matches('\n').count() + 1,
+                matches: ranges.len(),
+                snippet: document.snippet(&ranges),
+            });
+        }
+
+        hits.sort_by(|a, b| b.matches.cmp(&a.matches).then((a.year, a.day, a.kind).cmp(&(b.year, b.day, b.kind))));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+impl AocService {
+    /// Searches the statements, solver sources and notes, building the index first when there is none
+    /// or its files changed. Solver sources are built in, so only the files on disk are checked.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<AocSearchHit>, Box<dyn Error>> {
+        let mut index = self.search.index.lock().map_err(|_| "The search index is unavailable")?;
+
+        let fingerprint = self.search_fingerprint()?;
+        if index.as_ref().is_none_or(|i| i.fingerprint != fingerprint) {
+            *index = Some(self.build_search_index()?);
+        }
+
+        Ok(index.as_ref().map(|i| i.search(query, limit)).unwrap_or_default())
+    }
+
+    /// Builds the index now, returning how many documents are in it.
+    pub fn reindex_search(&self) -> Result<usize, Box<dyn Error>> {
+        let built = self.build_search_index()?;
+        let count = built.documents.len();
+        *self.search.index.lock().map_err(|_| "The search index is unavailable")? = Some(built);
+        Ok(count)
+    }
+
+    fn build_search_index(&self) -> Result<AocSearchIndex, Box<dyn Error>> {
+        // Taken first, a file changing while it is read is indexed again by the next search.
+        let fingerprint = self.search_fingerprint()?;
+        let documents = self.search_documents()?;
+        println!("Indexed {} documents for search", documents.len());
+        Ok(AocSearchIndex::new(fingerprint, documents))
+    }
+
+    /// The files under the years of the statement directories, the saved and imported statements
+    /// and notes, and the input sidecars.
+    fn search_fingerprint(&self) -> Result<AocSearchFingerprint, Box<dyn Error>> {
+        let mut fingerprint = AocSearchFingerprint::new();
+        let stamp = |path: &Path| -> Option<(SystemTime, u64)> {
+            let metadata = fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        };
+
+        for directory in [&self.statement_directory, &self.imported_statement_directory] {
+            // There is no imported statement directory before the first import.
+            if !Path::new(directory).is_dir() {
+                continue;
+            }
+            for dir_ent in read_dir(directory)? {
+                let dir_ent = dir_ent?;
+                if !dir_ent.file_type()?.is_dir() {
+                    continue;
+                }
+                for sub_dir_ent in read_dir(dir_ent.path())? {
+                    let path = sub_dir_ent?.path();
+                    fingerprint.insert(path.display().to_string(), stamp(&path));
+                }
+            }
+        }
+
+        let inputs = self.input_sources.index()?;
+        for name in inputs.files().into_iter().filter(|name| name.ends_with(".toml")) {
+            if let Some(layer) = inputs.find_writable_first(&name) {
+                fingerprint.insert(layer.location(&name), layer.stamp(&name));
+            }
+        }
+
+        Ok(fingerprint)
+    }
+
+    /// The description of every input that has one, as lines of the day's notes.
+    fn input_descriptions(&self) -> Result<AocDayNotes, Box<dyn Error>> {
+        let mut descriptions = AocDayNotes::new();
+        for input in self.list_all_inputs()? {
+            if let Some(description) = input.metadata.as_ref().and_then(|m| m.description.as_ref()) {
+                descriptions.entry((input.year, input.day)).or_default().push(format!("{}: {}", input.input, description));
+            }
+        }
+        Ok(descriptions)
+    }
+
+    fn search_documents(&self) -> Result<Vec<AocSearchDocument>, Box<dyn Error>> {
+        let mut documents = vec![];
+
+        for statement in self.list_statements()? {
+            match self.load_statement_text(statement.year, statement.day) {
+                Ok(Some(text)) => documents.push(AocSearchDocument::new(
+                    AocSearchKind::Statement,
+                    statement.year,
+                    statement.day,
+                    format!("Day {}: {}", statement.day, text.title),
+                    text.parts.iter().map(|part| html_text(part)).collect::<Vec<String>>().join("\n\n"))),
+                Ok(None) => {},
+                Err(error) => println!("Not indexing statement {}: {}", statement.path, error),
+            }
+        }
+
+        let mut sources = BTreeSet::new();
+        for (problem, solvers) in &self.problem_answers {
+            for source in solvers.iter().filter_map(|solver| solver.source()) {
+                if sources.insert(source.path) {
+                    documents.push(AocSearchDocument::new(
+                        AocSearchKind::Source,
+                        problem.year,
+                        problem.day,
+                        source.repository_path(),
+                        source.text.to_string()));
+                }
+            }
+        }
+
+        // Notes files and input descriptions go together, one document for each day.
+        let mut notes = AocDayNotes::new();
+        for dir_ent in read_dir(&self.statement_directory)? {
+            let dir_ent = dir_ent?;
+            let Some(year) = dir_ent.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            if !dir_ent.file_type()?.is_dir() {
+                continue;
+            }
+            for sub_dir_ent in read_dir(dir_ent.path())? {
+                let sub_dir_ent = sub_dir_ent?;
+                let file_name = sub_dir_ent.file_name();
+                let Some(caps) = file_name.to_str().and_then(|name| regex!(r"^day_(\d+)\.notes\.md$").captures(name)) else {
+                    continue;
+                };
+                notes.entry((year, caps[1].parse()?)).or_default().push(fs::read_to_string(sub_dir_ent.path())?);
+            }
+        }
+        for (key, descriptions) in self.input_descriptions()? {
+            notes.entry(key).or_default().extend(descriptions);
+        }
+        for ((year, day), lines) in notes {
+            documents.push(AocSearchDocument::new(
+                AocSearchKind::Notes,
+                year,
+                day,
+                format!("Day {} notes", day),
+                lines.join("\n")));
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    #[test]
+    fn searches_the_index_until_its_files_change() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("2022")).unwrap();
+        fs::write(directory.path().join("2022/day_11.notes.md"), "Keep the worry levels small with a modulus.").unwrap();
+        let aoc_service = AocService::new(AocInputSources::default(), directory.path().to_string_lossy().into_owned());
+
+        let hits = aoc_service.search("worry modulus", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].kind, hits[0].year, hits[0].day), (AocSearchKind::Notes, 2022, 11));
+
+        // Files changed outside the app are picked up by the next search.
+        fs::write(directory.path().join("2022/day_12.notes.md"), "Breadth first search up the hill.").unwrap();
+        assert_eq!(aoc_service.search("breadth", 10).unwrap().len(), 1);
+
+        fs::write(directory.path().join("2022/day_11.notes.md"), "Keep the worry levels small with a remainder.").unwrap();
+        assert!(aoc_service.search("worry modulus", 10).unwrap().is_empty());
+        assert_eq!(aoc_service.search("worry remainder", 10).unwrap().len(), 1);
+
+        fs::remove_file(directory.path().join("2022/day_12.notes.md")).unwrap();
+        assert!(aoc_service.search("breadth", 10).unwrap().is_empty());
+        assert_eq!(aoc_service.reindex_search().unwrap(), 1);
+    }
+
+    #[test]
+    fn matches_quoted_phrases_as_written() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("2022")).unwrap();
+        fs::write(directory.path().join("2022/day_11.notes.md"), "Test: divisible by 23, then the monkey throws.").unwrap();
+        let aoc_service = AocService::new(AocInputSources::default(), directory.path().to_string_lossy().into_owned());
+
+        assert_eq!(aoc_service.search("\"divisible by\"", 10).unwrap().len(), 1);
+        assert!(aoc_service.search("\"by divisible\"", 10).unwrap().is_empty());
+        assert!(aoc_service.search("divisible dijkstra", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use serde::Serialize;
+
+use crate::rookrustpad::service::aoc::AocService;
+
+/// Where solver sources live in the repository, AocSource paths are relative to it.
+pub const SOLVER_SOURCE_ROOT: &str = "src/rookrustpad/service/aoc";
+
+/// A solver's source file, embedded at build time.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub struct AocSource {
+    pub path: &'static str,
+    #[serde(skip)]
+    pub text: &'static str,
+}
+
+/// First and last line, counting from 1, of a function in a source file.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub struct AocSourceSpan {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl AocSource {
+    pub fn repository_path(&self) -> String {
+        format!("{}/{}", SOLVER_SOURCE_ROOT, self.path)
+    }
+
+    /// Finds a top level `fn` by name and follows its braces to the end.
+    /// Braces inside strings, chars and comments are skipped.
+    pub fn find_function(&self, function: &str) -> Option<AocSourceSpan> {
+        let signature = format!("fn {}", function);
+        let start = self.text.match_indices(&signature).map(|(i, _)| i).find(|i| {
+            let after = self.text[i + signature.len()..].chars().next();
+            matches!(after, Some('(') | Some('<') | Some(' '))
+        })?;
+
+        let bytes = self.text.as_bytes();
+        let mut depth = 0;
+        let mut i = start;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                },
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i += 2;
+                    while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                        i += 1;
+                    }
+                    i += 1;
+                },
+                b'"' => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != b'"' {
+                        if bytes[i] == b'\\' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                },
+                // A char literal, lifetimes have no closing quote two or three bytes on.
+                b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+                b'\'' if bytes.get(i + 1) == Some(&b'\\') && bytes.get(i + 3) == Some(&b'\'') => i += 3,
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(AocSourceSpan {
+                            start_line: line_of(self.text, start),
+                            end_line: line_of(self.text, i),
+                        });
+                    }
+                },
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
+    }
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocSolverSource {
+    pub part: u8,
+    pub variant: String,
+    pub name: String,
+    pub span: Option<AocSourceSpan>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocSourceFile {
+    pub path: String,
+    pub source: &'static str,
+    pub solvers: Vec<AocSolverSource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocDaySource {
+    pub year: u32,
+    pub day: u32,
+    pub files: Vec<AocSourceFile>,
+}
+
+impl AocService {
+    /// The source files of every native solver for a day, with where each solver's function is.
+    pub fn get_day_source(&self, year: u32, day: u32) -> AocDaySource {
+        let mut files: Vec<AocSourceFile> = vec![];
+
+        for problem in self.list_problems().into_iter().filter(|p| p.year == year && p.day == day) {
+            for solver in &self.problem_answers[&problem] {
+                let Some(source) = solver.source() else {
+                    continue;
+                };
+
+                let solver_source = AocSolverSource {
+                    part: problem.part,
+                    variant: solver.variant.clone(),
+                    name: solver.name.clone(),
+                    span: solver.function_span(),
+                };
+
+                let path = source.repository_path();
+                match files.iter_mut().find(|f| f.path == path) {
+                    Some(file) => file.solvers.push(solver_source),
+                    None => files.push(AocSourceFile {
+                        path,
+                        source: source.text,
+                        solvers: vec![solver_source],
+                    }),
+                }
+            }
+        }
+
+        AocDaySource { year, day, files }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    #[test]
+    fn finds_the_span_of_a_solver() {
+        let aoc_service = AocService::create_default(AocInputSources::new(vec![]), String::from("www/aoc_problems"));
+        let solver = aoc_service.get_solver_variant(2022, 11, 2, &Some(String::from("naive"))).unwrap();
+        assert_eq!(solver.name, "y2022::day11::part2");
+
+        let source = solver.source().unwrap();
+        let span = solver.function_span().unwrap();
+        let lines: Vec<&str> = source.text.lines().collect();
+        assert!(lines[span.start_line - 1].starts_with("pub fn part2("), "{}", lines[span.start_line - 1]);
+        assert_eq!(lines[span.end_line - 1], "}");
+        assert!(lines[span.start_line..span.end_line].iter().any(|l| l.contains("run_rounds_sampled")));
+    }
+
+    #[test]
+    fn skips_braces_in_strings_chars_and_comments() {
+        let source = AocSource {
+            path: "y2022/day00.rs",
+            text: "fn part1_helper() {}\n\nfn part1<'a>(s: &'a str) {\n    // }\n    let _ = \"}\\\"}\";\n    let _ = '}';\n    /* } */\n}\n\nfn part2() {}\n",
+        };
+
+        assert_eq!(source.find_function("part1"), Some(AocSourceSpan { start_line: 3, end_line: 8 }));
+        assert_eq!(source.find_function("part2"), Some(AocSourceSpan { start_line: 10, end_line: 10 }));
+        assert_eq!(source.find_function("part3"), None);
+    }
+}
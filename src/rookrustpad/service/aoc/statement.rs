@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use lazy_regex::regex;
+use lazy_regex::regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::AocService;
+
+pub const DEFAULT_IMPORTED_STATEMENT_DIRECTORY: &str = "data/aoc_statements";
+
+/// Where relative links in a statement point.
+const AOC_SITE: &str = "https://adventofcode.com";
+
+/// Tags kept in an imported statement, everything else is dropped but its text is kept.
+const ALLOWED_TAGS: &[&str] = &["h2", "p", "em", "code", "pre", "ul", "ol", "li", "a", "span", "br", "sup"];
+
+/// The puzzle description of a day, cleaned up for reading inside the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocStatementText {
+    pub year: u32,
+    pub day: u32,
+    /// The puzzle's name, "Monkey in the Middle".
+    pub title: String,
+    /// Each part's description as html, only the allowed tags remain.
+    pub parts: Vec<String>,
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    regex!(r#"([a-zA-Z-]+)\s*=\s*"([^"]*)""#)
+        .captures_iter(tag)
+        .find(|caps| caps[1].eq_ignore_ascii_case(name))
+        .map(|caps| caps[2].to_string())
+}
+
+/// Links go to the web or back to AoC, anything else (javascript:, files) is dropped.
+fn safe_href(href: &str) -> Option<String> {
+    if href.starts_with("https://") || href.starts_with("http://") {
+        Some(href.to_string())
+    }
+    else if href.starts_with('/') && !href.starts_with("//") {
+        Some(format!("{}{}", AOC_SITE, href))
+    }
+    else {
+        None
+    }
+}
+
+/// Keeps the allowed tags without their attributes, except a link's href and a span's
+/// title which hold the puzzle's links and jokes. Scripts, styles and comments are removed.
+pub fn sanitize_html(html: &str) -> String {
+    let html = regex!(r"(?is)<!--.*?-->|<(script|style|noscript)\b.*?</(script|style|noscript)\s*>").replace_all(html, "");
+
+    let mut clean = String::new();
+    let mut position = 0;
+    for caps in regex!(r"<(/?)([a-zA-Z0-9]+)([^>]*)>").captures_iter(&html) {
+        let Some(tag) = caps.get(0) else {
+            continue;
+        };
+        clean.push_str(&html[position..tag.start()].replace('<', "&lt;"));
+        position = tag.end();
+
+        let closing = &caps[1] == "/";
+        let name = caps[2].to_lowercase();
+        if !ALLOWED_TAGS.contains(&name.as_str()) {
+            continue;
+        }
+
+        if closing {
+            clean.push_str(&format!("</{}>", name));
+            continue;
+        }
+
+        let attributes = match name.as_str() {
+            "a" => attribute(&caps[3], "href")
+                .and_then(|href| safe_href(&href))
+                .map(|href| format!(" href=\"{}\" rel=\"noopener noreferrer\"", href)),
+            "span" => attribute(&caps[3], "title").map(|title| format!(" title=\"{}\"", title)),
+            _ => None,
+        };
+        clean.push_str(&format!("<{}{}>", name, attributes.unwrap_or_default()));
+    }
+    clean.push_str(&html[position..].replace('<', "&lt;"));
+
+    compact(&clean)
+}
+
+/// Saved pages are wrapped at odd places, so outside of code blocks line breaks are
+/// folded into spaces and each block starts on its own line instead.
+fn compact(html: &str) -> String {
+    let mut compacted = String::new();
+    let mut position = 0;
+    for block in regex!(r"(?s)<pre>.*?</pre>").find_iter(html) {
+        compacted.push_str(compact_text(&html[position..block.start()]).trim_end());
+        compacted.push('\n');
+        compacted.push_str(block.as_str());
+        position = block.end();
+    }
+    compacted.push_str(&compact_text(&html[position..]));
+    compacted
+}
+
+fn compact_text(html: &str) -> String {
+    let folded = regex!(r"\s*\n\s*").replace_all(html, " ");
+    regex!(r"\s*(<(?:p|h2|pre|ul|ol|li)>)").replace_all(&folded, "\n$1").to_string()
+}
+
+/// Strips tags and decodes the entities a statement uses.
+pub fn html_text(html: &str) -> String {
+    regex!(r"<[^>]*>")
+        .replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+impl AocStatementText {
+    /// Reads the parts out of html with an article for each of them, a saved page or an imported statement.
+    fn from_articles(year: u32, day: u32, html: &str, article_re: &Regex) -> AocStatementText {
+        let parts: Vec<String> = article_re
+            .captures_iter(html)
+            .map(|caps| sanitize_html(&caps[1]).trim().to_string())
+            .collect();
+
+        // "--- Day 11: Monkey in the Middle ---" is shortened to the puzzle's name.
+        let title = parts.first()
+            .and_then(|part| regex!(r"(?s)<h2>(.*?)</h2>").captures(part))
+            .map(|caps| regex!(r"<[^>]*>").replace_all(&caps[1], "").to_string())
+            .map(|title| title.trim_matches(|c: char| c == '-' || c.is_whitespace()).to_string())
+            .map(|title| match title.split_once(": ") {
+                Some((_, name)) => name.to_string(),
+                None => title,
+            })
+            .unwrap_or_else(|| format!("Day {}", day));
+
+        AocStatementText { year, day, title, parts }
+    }
+
+    /// The compact html the statement is stored as, an article for each part.
+    pub fn to_html(&self) -> String {
+        self.parts.iter().map(|part| format!("<article>\n{}\n</article>\n", part)).collect()
+    }
+}
+
+impl AocService {
+    /// Where the cleaned up statement is kept.
+    pub fn get_statement_text_path(&self, year: u32, day: u32) -> String {
+        format!("{}/{}/day_{}.statement.html", self.imported_statement_directory, year, day)
+    }
+
+    /// Reads the part descriptions out of a saved statement page.
+    fn read_saved_statement(&self, year: u32, day: u32) -> Result<AocStatementText, Box<dyn Error>> {
+        let statement_path = self.get_statement_path(year, day);
+        let page = fs::read_to_string(&statement_path)
+            .map_err(|e| format!("Unable to read {}: {}", statement_path, e))?;
+
+        let statement = AocStatementText::from_articles(year, day, &page, regex!(r#"(?is)<article class="day-desc">(.*?)</article>"#));
+        if statement.parts.is_empty() {
+            return Err(format!("No puzzle description in {}", statement_path).into());
+        }
+        Ok(statement)
+    }
+
+    /// Reads the part descriptions out of a saved statement page and stores them cleaned up.
+    pub fn import_statement(&self, year: u32, day: u32) -> Result<AocStatementText, Box<dyn Error>> {
+        let statement = self.read_saved_statement(year, day)?;
+        let text_path = self.get_statement_text_path(year, day);
+        if let Some(directory) = Path::new(&text_path).parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&text_path, statement.to_html())?;
+        self.search.invalidate();
+        Ok(statement)
+    }
+
+    /// True when the saved page is newer than its import or was never imported.
+    fn is_import_stale(&self, year: u32, day: u32) -> bool {
+        let saved = fs::metadata(self.get_statement_path(year, day)).and_then(|m| m.modified()).ok();
+        let imported = fs::metadata(self.get_statement_text_path(year, day)).and_then(|m| m.modified()).ok();
+        match (saved, imported) {
+            (Some(saved), Some(imported)) => saved > imported,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Imports the saved pages that changed since they were imported, done when the server starts.
+    pub fn import_changed_statements(&self) -> Result<usize, Box<dyn Error>> {
+        let mut imported = 0;
+        for statement in self.list_statements()? {
+            if !self.is_import_stale(statement.year, statement.day) {
+                continue;
+            }
+            match self.import_statement(statement.year, statement.day) {
+                Ok(_) => imported += 1,
+                Err(error) => println!("Unable to import {}: {}", statement.path, error),
+            }
+        }
+        Ok(imported)
+    }
+
+    /// The cleaned up statement. Nothing is written, a saved page that is newer or was never
+    /// imported is cleaned up again on every read until it is imported.
+    pub fn load_statement_text(&self, year: u32, day: u32) -> Result<Option<AocStatementText>, Box<dyn Error>> {
+        let text_path = self.get_statement_text_path(year, day);
+        if self.is_import_stale(year, day) {
+            return Ok(Some(self.read_saved_statement(year, day)?));
+        }
+
+        match fs::metadata(&text_path) {
+            Ok(_) => {
+                // Cleaned again on the way in, the file may have been edited by hand.
+                let html = fs::read_to_string(&text_path)?;
+                Ok(Some(AocStatementText::from_articles(year, day, &html, regex!(r"(?is)<article>(.*?)</article>"))))
+            },
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    #[test]
+    fn keeps_allowed_tags_without_their_attributes() {
+        assert_eq!(
+            sanitize_html(r#"<p class="x" onclick="steal()">The <em style="color: red">monkeys</em> <code>x</code></p>"#),
+            "\n<p>The <em>monkeys</em> <code>x</code></p>");
+    }
+
+    #[test]
+    fn drops_other_tags_but_keeps_their_text() {
+        assert_eq!(sanitize_html(r#"<p><div><b>bold</b> <img src="x.png"> text</div></p>"#), "\n<p>bold  text</p>");
+    }
+
+    #[test]
+    fn removes_scripts_styles_and_comments() {
+        let html = "<p>a<script>alert(1)</script><STYLE>p {}</STYLE><!-- hidden <p> --><noscript>no</noscript>b</p>";
+        assert_eq!(sanitize_html(html), "\n<p>ab</p>");
+    }
+
+    #[test]
+    fn keeps_safe_links_only() {
+        assert_eq!(
+            sanitize_html(r#"<a href="/2022/day/10" target="_blank">day 10</a>"#),
+            r#"<a href="https://adventofcode.com/2022/day/10" rel="noopener noreferrer">day 10</a>"#);
+        assert_eq!(
+            sanitize_html(r#"<a href="https://en.wikipedia.org/wiki/Monkey">monkey</a>"#),
+            r#"<a href="https://en.wikipedia.org/wiki/Monkey" rel="noopener noreferrer">monkey</a>"#);
+        assert_eq!(sanitize_html(r#"<a href="javascript:alert(1)">x</a>"#), "<a>x</a>");
+        assert_eq!(sanitize_html(r#"<a href="//evil.example/">x</a>"#), "<a>x</a>");
+    }
+
+    #[test]
+    fn keeps_the_hover_jokes() {
+        assert_eq!(
+            sanitize_html(r#"<span title="Monkeys are fun" style="x">tossing</span>"#),
+            r#"<span title="Monkeys are fun">tossing</span>"#);
+    }
+
+    #[test]
+    fn escapes_stray_angle_brackets() {
+        assert_eq!(sanitize_html("<p>a < b</p>"), "\n<p>a &lt; b</p>");
+    }
+
+    #[test]
+    fn folds_lines_outside_code_blocks() {
+        let html = "<p>Monkey 0:\n  Starting items</p>\n<pre><code>Monkey 0:\n  Starting items: 79\n</code></pre>";
+        assert_eq!(
+            sanitize_html(html),
+            "\n<p>Monkey 0: Starting items</p>\n<pre><code>Monkey 0:\n  Starting items: 79\n</code></pre>");
+    }
+
+    #[test]
+    fn reads_the_title_and_parts_of_a_saved_page() {
+        let page = r#"<main><article class="day-desc"><h2>--- Day 11: Monkey in the Middle ---</h2><p>One</p></article>
+            <p>Your puzzle answer was <code>1</code>.</p>
+            <article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Two</p></article></main>"#;
+        let text = AocStatementText::from_articles(2022, 11, page, regex!(r#"(?is)<article class="day-desc">(.*?)</article>"#));
+
+        assert_eq!(text.title, "Monkey in the Middle");
+        assert_eq!(text.parts, vec!["<h2>--- Day 11: Monkey in the Middle ---</h2>\n<p>One</p>", "<h2>--- Part Two ---</h2>\n<p>Two</p>"]);
+    }
+
+    #[test]
+    fn reading_never_imports_and_startup_imports_changed_pages() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("2022")).unwrap();
+        fs::write(
+            directory.path().join("2022/Day 11 - Advent of Code 2022.html"),
+            r#"<article class="day-desc"><h2>--- Day 11: Monkey in the Middle ---</h2><p>One</p></article>"#).unwrap();
+        let mut aoc_service = AocService::new(AocInputSources::default(), directory.path().to_string_lossy().into_owned());
+        aoc_service.imported_statement_directory = directory.path().join("imported").to_string_lossy().into_owned();
+        let text_path = aoc_service.get_statement_text_path(2022, 11);
+        assert!(text_path.starts_with(&aoc_service.imported_statement_directory));
+
+        let text = aoc_service.load_statement_text(2022, 11).unwrap().unwrap();
+        assert_eq!(text.title, "Monkey in the Middle");
+        assert!(!Path::new(&text_path).exists());
+
+        assert_eq!(aoc_service.import_changed_statements().unwrap(), 1);
+        assert!(Path::new(&text_path).exists());
+        assert_eq!(aoc_service.import_changed_statements().unwrap(), 0);
+        assert_eq!(aoc_service.load_statement_text(2022, 11).unwrap().unwrap().parts, text.parts);
+        assert!(aoc_service.load_statement_text(2022, 12).unwrap().is_none());
+    }
+}
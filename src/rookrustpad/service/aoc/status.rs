@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::rookrustpad::service::aoc::history::AocRun;
+use crate::rookrustpad::service::aoc::{AocProblem, AocService, AocVerdict};
+
+pub const DAYS_PER_YEAR: u32 = 25;
+pub const PARTS_PER_DAY: u8 = 2;
+
+/// Puzzles unlock at midnight in US Eastern time, 05:00 UTC.
+pub fn unlock_time(year: u32, day: u32) -> Option<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(year as i32, 12, day, 5, 0, 0).single()
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub enum AocStar {
+    /// The main input's answer matched the expected one.
+    Verified,
+    /// Ran fine, but there is no expected answer to check against.
+    Unverified,
+    /// The run errored or gave the wrong answer.
+    Failed,
+    NotRun,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocPartStatus {
+    pub part: u8,
+    pub implemented: bool,
+    pub datasets: Vec<Option<String>>,
+    pub star: AocStar,
+    pub latest_run: Option<AocRun>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AocDayStatus {
+    pub year: u32,
+    pub day: u32,
+    pub statement: Option<String>,
+    pub parts: Vec<AocPartStatus>,
+}
+
+impl AocDayStatus {
+    pub fn has_solver(&self) -> bool {
+        self.parts.iter().any(|p| p.implemented)
+    }
+
+    pub fn has_inputs(&self) -> bool {
+        self.parts.iter().any(|p| !p.datasets.is_empty())
+    }
+
+    /// Something exists for the day, but the statement or solver is missing.
+    pub fn is_incomplete(&self) -> bool {
+        let started = self.statement.is_some() || self.has_solver() || self.has_inputs();
+        started && (self.statement.is_none() || !self.has_solver())
+    }
+}
+
+fn star_for(run: Option<&AocRun>) -> AocStar {
+    match run.map(|r| r.answer.verdict()) {
+        None => AocStar::NotRun,
+        Some(AocVerdict::Failed | AocVerdict::Incorrect) => AocStar::Failed,
+        Some(AocVerdict::Correct) => AocStar::Verified,
+        Some(AocVerdict::Unverified) => AocStar::Unverified,
+    }
+}
+
+impl AocService {
+    /// Every year that has a solver, an input or a saved statement.
+    pub fn list_years(&self) -> Result<Vec<u32>, Box<dyn Error>> {
+        let mut years: BTreeSet<u32> = self.problem_answers.keys().map(|p| p.year).collect();
+        years.extend(self.list_inputs()?.iter().map(|i| i.year));
+        years.extend(self.list_statements()?.iter().map(|s| s.year));
+        Ok(years.into_iter().collect())
+    }
+
+    /// The calendar of a user, or of the shared inputs. Stars only come from runs on the
+    /// main input with the dataset's own parameters, test datasets don't earn them.
+    pub fn get_day_statuses(&self, year: u32, user: &Option<String>) -> Result<Vec<AocDayStatus>, Box<dyn Error>> {
+        let inputs = self.list_user_inputs(user)?;
+
+        let statuses = (1..=DAYS_PER_YEAR).map(|day| {
+            let statement_path = self.get_statement_path(year, day);
+            let statement = Path::new(&statement_path).exists().then_some(statement_path);
+
+            let parts = (1..=PARTS_PER_DAY).map(|part| {
+                let run = self.history.latest_default(year, day, part, user);
+                AocPartStatus {
+                    part,
+                    implemented: self.problem_answers.contains_key(&AocProblem { year, day, part }),
+                    datasets: inputs
+                        .iter()
+                        .filter(|i| i.year == year && i.day == day && i.part == part)
+                        .map(|i| i.dataset.clone())
+                        .collect(),
+                    star: star_for(run.as_ref()),
+                    latest_run: run,
+                }
+            }).collect();
+
+            AocDayStatus { year, day, statement, parts }
+        }).collect();
+
+        Ok(statuses)
+    }
+}
@@ -0,0 +1,418 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lazy_regex::regex;
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::metadata::AocInputMetadata;
+use crate::rookrustpad::service::aoc::statement::html_text;
+use crate::rookrustpad::service::aoc::AocService;
+
+/// What AoC made of a submitted answer.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AocSubmissionVerdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong without a hint which way.
+    Incorrect,
+    /// Sent during a cooldown, the answer wasn't checked.
+    Wait,
+    /// The part was already solved on the website.
+    AlreadySolved,
+    /// A reply that couldn't be read.
+    Unknown,
+    /// No reply, the request failed and the message says why.
+    Failed,
+}
+
+impl AocSubmissionVerdict {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, AocSubmissionVerdict::TooHigh | AocSubmissionVerdict::TooLow | AocSubmissionVerdict::Incorrect)
+    }
+}
+
+/// An answer sent to AoC and its reply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocSubmission {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub answer: String,
+    /// Milliseconds since the epoch.
+    pub submitted_at: u64,
+    pub verdict: AocSubmissionVerdict,
+    /// Seconds AoC asked to wait before the next answer.
+    pub wait: Option<u64>,
+    /// The reply's text.
+    pub message: String,
+}
+
+impl AocSubmission {
+    /// Milliseconds since the epoch when another answer can be sent.
+    pub fn cooldown_until(&self) -> Option<u64> {
+        self.wait.map(|wait| self.submitted_at + wait * 1000)
+    }
+}
+
+/// Every submission, appended to a json lines file so they survive restarts.
+#[derive(Debug)]
+pub struct AocSubmissionLog {
+    pub path: String,
+    lock: Mutex<()>,
+    /// Held from checking the earlier attempts until the reply is logged, one answer is sent at a time.
+    sending: Mutex<()>,
+}
+
+impl AocSubmissionLog {
+    pub fn new(path: &str) -> AocSubmissionLog {
+        AocSubmissionLog { path: path.to_string(), lock: Mutex::new(()), sending: Mutex::new(()) }
+    }
+
+    pub fn load(&self) -> Result<Vec<AocSubmission>, Box<dyn Error>> {
+        let _lock = self.lock.lock().map_err(|_| "The submission log is unavailable")?;
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(format!("Unable to read {}: {}", self.path, error).into()),
+        };
+
+        let mut submissions = vec![];
+        for (n, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            submissions.push(serde_json::from_str(line).map_err(|e| format!("Invalid submission at {}:{}: {}", self.path, n + 1, e))?);
+        }
+        Ok(submissions)
+    }
+
+    pub fn append(&self, submission: &AocSubmission) -> Result<(), Box<dyn Error>> {
+        let _lock = self.lock.lock().map_err(|_| "The submission log is unavailable")?;
+        if let Some(directory) = Path::new(&self.path).parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(directory)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(submission)?)?;
+        Ok(())
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Seconds to wait from "you have 4m 32s left to wait" or "Please wait one minute before trying again".
+fn parse_wait(message: &str) -> Option<u64> {
+    if let Some(caps) = regex!(r"(?:(\d+)m )?(\d+)s left to wait").captures(message) {
+        let minutes: u64 = caps.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        let seconds: u64 = caps[2].parse().ok()?;
+        return Some(minutes * 60 + seconds);
+    }
+
+    let caps = regex!(r"wait (\w+) minutes?").captures(message)?;
+    let minutes = match &caps[1] {
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "ten" => 10,
+        number => number.parse().ok()?,
+    };
+    Some(minutes * 60)
+}
+
+/// Reads the verdict out of the page AoC answers a submission with.
+pub fn parse_response(html: &str) -> (AocSubmissionVerdict, Option<u64>, String) {
+    let article = regex!(r"(?s)<article>(.*?)</article>")
+        .captures(html)
+        .map(|caps| caps[1].to_string())
+        .unwrap_or_else(|| html.to_string());
+    let message = regex!(r"\s+").replace_all(html_text(&article).trim(), " ").to_string();
+
+    let verdict = if message.contains("That's the right answer") {
+        AocSubmissionVerdict::Correct
+    }
+    else if message.contains("too high") {
+        AocSubmissionVerdict::TooHigh
+    }
+    else if message.contains("too low") {
+        AocSubmissionVerdict::TooLow
+    }
+    else if message.contains("That's not the right answer") {
+        AocSubmissionVerdict::Incorrect
+    }
+    else if message.contains("You gave an answer too recently") {
+        AocSubmissionVerdict::Wait
+    }
+    else if message.contains("already complete it") {
+        AocSubmissionVerdict::AlreadySolved
+    }
+    else {
+        AocSubmissionVerdict::Unknown
+    };
+
+    (verdict, parse_wait(&message), message)
+}
+
+/// Why an answer shouldn't be sent, from what the earlier attempts for the part found out.
+fn check_submission(attempts: &[AocSubmission], answer: &str, now: u64) -> Result<(), String> {
+    if let Some(correct) = attempts.iter().find(|a| a.verdict == AocSubmissionVerdict::Correct) {
+        return Err(format!("The part was already solved with {}", correct.answer));
+    }
+    if attempts.iter().any(|a| a.answer == answer && a.verdict.is_wrong()) {
+        return Err(format!("{} was already submitted and is wrong", answer));
+    }
+
+    // Too high and too low bound the answer, anything past them is wrong too.
+    if let Ok(value) = answer.parse::<i128>() {
+        for attempt in attempts {
+            let Ok(attempted) = attempt.answer.parse::<i128>() else {
+                continue;
+            };
+            if attempt.verdict == AocSubmissionVerdict::TooHigh && value >= attempted {
+                return Err(format!("{} is too high, {} already was", answer, attempt.answer));
+            }
+            if attempt.verdict == AocSubmissionVerdict::TooLow && value <= attempted {
+                return Err(format!("{} is too low, {} already was", answer, attempt.answer));
+            }
+        }
+    }
+
+    if let Some(until) = attempts.iter().filter_map(|a| a.cooldown_until()).max().filter(|until| *until > now) {
+        return Err(format!("AoC asked to wait, try again in {} seconds", (until - now).div_ceil(1000)));
+    }
+
+    Ok(())
+}
+
+impl AocService {
+    /// The submissions for a part, oldest first.
+    pub fn list_submissions(&self, year: u32, day: u32, part: u8) -> Result<Vec<AocSubmission>, Box<dyn Error>> {
+        Ok(self.submissions.load()?
+            .into_iter()
+            .filter(|s| s.year == year && s.day == day && s.part == part)
+            .collect())
+    }
+
+    /// Sends an answer to AoC unless an earlier attempt shows it is wrong or AoC asked to wait.
+    /// Every reply is logged, and a correct answer becomes the main input's expected answer.
+    pub fn submit_answer(&self, year: u32, day: u32, part: u8, answer: &str) -> Result<AocSubmission, Box<dyn Error>> {
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Err("There is no answer to submit".into());
+        }
+
+        let _sending = self.submissions.sending.lock().map_err(|_| "The submission log is unavailable")?;
+        let attempts = self.list_submissions(year, day, part)?;
+        check_submission(&attempts, answer, now_millis())?;
+
+        let part_text = part.to_string();
+        let response = self.client.post_form(
+            &format!("/{}/day/{}/answer", year, day),
+            &[("level", part_text.as_str()), ("answer", answer)]);
+
+        let (verdict, wait, message) = match &response {
+            Ok(response) => parse_response(response),
+            Err(error) => {
+                println!("Unable to submit {} for {}/{}/{}: {}", answer, year, day, part, error);
+                (AocSubmissionVerdict::Failed, None, error.to_string())
+            },
+        };
+        let submission = AocSubmission {
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            submitted_at: now_millis(),
+            verdict,
+            wait,
+            message,
+        };
+        self.submissions.append(&submission)?;
+        response?;
+
+        if verdict == AocSubmissionVerdict::Correct {
+            self.save_expected_answer(year, day, part, answer)?;
+            self.attach_submission(year, day, part, answer)?;
+        }
+
+        Ok(submission)
+    }
+
+    /// Writes an answer into the sidecar of a part's main input, keeping what else it holds.
+    pub fn save_expected_answer(&self, year: u32, day: u32, part: u8, answer: &str) -> Result<String, Box<dyn Error>> {
+        let input_name = self.get_input_name(&None, year, day, part, &None);
+        let mut metadata = self.load_input_metadata(&input_name)?.unwrap_or_default();
+        metadata.expected.insert(part.to_string(), answer.to_string());
+
+        self.input_sources.write_file(&AocInputMetadata::sidecar_name(&input_name), toml::to_string(&metadata)?.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::rookrustpad::service::aoc::client::{AocClient, AocClientSettings};
+    use crate::rookrustpad::service::aoc::input_source::directory::DirectoryInputSource;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+
+    const CORRECT: &str = r#"<main><article><p>That's the right answer!  You are <span class="day-success">one gold star</span> closer to
+        collecting enough star fruit. <a href="/2022/day/11#part2">[Continue to Part Two]</a></p></article></main>"#;
+    const TOO_HIGH: &str = r#"<main><article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure
+        you're using the full input data. Please wait one minute before trying again. <a href="/2022/day/11">[Return to Day 11]</a></p></article></main>"#;
+    const TOO_LOW: &str = r#"<main><article><p>That's not the right answer; your answer is too low.  Please wait 5 minutes
+        before trying again. <a href="/2022/day/11">[Return to Day 11]</a></p></article></main>"#;
+    const INCORRECT: &str = r#"<main><article><p>That's not the right answer.  If you're stuck, make sure you're using the full input
+        data. Please wait one minute before trying again.</p></article></main>"#;
+    const TOO_RECENTLY: &str = r#"<main><article><p>You gave an answer too recently; you have to wait after submitting an answer
+        before trying again.  You have 4m 32s left to wait. <a href="/2022/day/11">[Return to Day 11]</a></p></article></main>"#;
+    const ALREADY_SOLVED: &str = r#"<main><article><p>You don't seem to be solving the right level.  Did you already complete it?
+        <a href="/2022/day/11">[Return to Day 11]</a></p></article></main>"#;
+
+    fn attempt(answer: &str, verdict: AocSubmissionVerdict, submitted_at: u64, wait: Option<u64>) -> AocSubmission {
+        AocSubmission {
+            year: 2022,
+            day: 11,
+            part: 1,
+            answer: answer.to_string(),
+            submitted_at,
+            verdict,
+            wait,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn reads_the_waits_aoc_asks_for() {
+        assert_eq!(parse_wait("Please wait one minute before trying again."), Some(60));
+        assert_eq!(parse_wait("Please wait 5 minutes before trying again."), Some(300));
+        assert_eq!(parse_wait("please wait ten minutes"), Some(600));
+        assert_eq!(parse_wait("You have 4m 32s left to wait."), Some(272));
+        assert_eq!(parse_wait("You have 34s left to wait."), Some(34));
+        assert_eq!(parse_wait("That's the right answer!"), None);
+        assert_eq!(parse_wait("Please wait many minutes"), None);
+    }
+
+    #[test]
+    fn reads_each_verdict() {
+        let (verdict, wait, message) = parse_response(CORRECT);
+        assert_eq!((verdict, wait), (AocSubmissionVerdict::Correct, None));
+        assert!(message.starts_with("That's the right answer! You are one gold star closer"), "{}", message);
+
+        assert_eq!(parse_response(TOO_HIGH).0, AocSubmissionVerdict::TooHigh);
+        assert_eq!(parse_response(TOO_HIGH).1, Some(60));
+        assert_eq!(parse_response(TOO_LOW).0, AocSubmissionVerdict::TooLow);
+        assert_eq!(parse_response(TOO_LOW).1, Some(300));
+        assert_eq!(parse_response(INCORRECT).0, AocSubmissionVerdict::Incorrect);
+        assert_eq!(parse_response(TOO_RECENTLY).0, AocSubmissionVerdict::Wait);
+        assert_eq!(parse_response(TOO_RECENTLY).1, Some(272));
+        assert_eq!(parse_response(ALREADY_SOLVED).0, AocSubmissionVerdict::AlreadySolved);
+        assert_eq!(parse_response("<html>Maintenance</html>").0, AocSubmissionVerdict::Unknown);
+    }
+
+    #[test]
+    fn only_the_article_is_kept_as_the_message() {
+        let (_, _, message) = parse_response(r#"<nav>[Events]</nav><article><p>You gave an answer too recently.</p></article><footer>x</footer>"#);
+        assert_eq!(message, "You gave an answer too recently.");
+    }
+
+    #[test]
+    fn holds_back_answers_of_a_solved_part() {
+        let attempts = [attempt("10", AocSubmissionVerdict::Correct, 0, None)];
+        assert_eq!(check_submission(&attempts, "11", 0), Err(String::from("The part was already solved with 10")));
+    }
+
+    #[test]
+    fn holds_back_answers_already_wrong() {
+        let attempts = [attempt("abc", AocSubmissionVerdict::Incorrect, 0, Some(60))];
+        assert_eq!(check_submission(&attempts, "abc", 120_000), Err(String::from("abc was already submitted and is wrong")));
+        assert_eq!(check_submission(&attempts, "abd", 120_000), Ok(()));
+    }
+
+    #[test]
+    fn reuses_the_too_high_and_too_low_bounds() {
+        let attempts = [
+            attempt("100", AocSubmissionVerdict::TooHigh, 0, Some(60)),
+            attempt("40", AocSubmissionVerdict::TooLow, 0, Some(60)),
+        ];
+        let now = 120_000;
+
+        assert_eq!(check_submission(&attempts, "150", now), Err(String::from("150 is too high, 100 already was")));
+        assert_eq!(check_submission(&attempts, "101", now), Err(String::from("101 is too high, 100 already was")));
+        assert_eq!(check_submission(&attempts, "12", now), Err(String::from("12 is too low, 40 already was")));
+        assert_eq!(check_submission(&attempts, "39", now), Err(String::from("39 is too low, 40 already was")));
+        assert_eq!(check_submission(&attempts, "41", now), Ok(()));
+        assert_eq!(check_submission(&attempts, "99", now), Ok(()));
+        // Bounds only hold for numbers.
+        assert_eq!(check_submission(&attempts, "ABCD", now), Ok(()));
+    }
+
+    #[test]
+    fn waits_out_the_cooldown() {
+        let attempts = [
+            attempt("100", AocSubmissionVerdict::TooHigh, 1_000, Some(60)),
+            attempt("90", AocSubmissionVerdict::Wait, 2_000, Some(272)),
+        ];
+
+        assert_eq!(check_submission(&attempts, "50", 10_000), Err(String::from("AoC asked to wait, try again in 264 seconds")));
+        assert_eq!(check_submission(&attempts, "50", 273_500), Err(String::from("AoC asked to wait, try again in 1 seconds")));
+        assert_eq!(check_submission(&attempts, "50", 274_000), Ok(()));
+    }
+
+    #[test]
+    fn saved_answers_are_read_back_when_the_input_is_elsewhere() {
+        // The saved sidecar goes to the first writable source, the input is in the second.
+        let saved = tempfile::tempdir().unwrap();
+        let inputs = tempfile::tempdir().unwrap();
+        fs::create_dir(inputs.path().join("2022")).unwrap();
+        fs::write(inputs.path().join("2022/day_11_1.txt"), "Monkey 0:\n").unwrap();
+        fs::write(inputs.path().join("2022/day_11_1.toml"), "description = \"Mine\"\n").unwrap();
+        let aoc_service = AocService::new(
+            AocInputSources::new(vec![
+                Box::new(DirectoryInputSource::new(&saved.path().to_string_lossy())),
+                Box::new(DirectoryInputSource::new(&inputs.path().to_string_lossy())),
+            ]),
+            String::from("www/aoc_problems"));
+
+        aoc_service.save_expected_answer(2022, 11, 1, "10605").unwrap();
+
+        assert!(saved.path().join("2022/day_11_1.toml").is_file());
+        let metadata = aoc_service.load_input_metadata("2022/day_11_1.txt").unwrap().unwrap();
+        assert_eq!(metadata.expected_answer(1), Some(&String::from("10605")));
+        assert_eq!(metadata.description.as_deref(), Some("Mine"));
+        assert_eq!(aoc_service.load_input_metadata("2022/day_11_1.txt").unwrap().and_then(|m| m.expected_answer(1).cloned()), Some(String::from("10605")));
+    }
+
+    #[test]
+    fn logs_answers_that_could_not_be_sent() {
+        let directory = tempfile::tempdir().unwrap();
+        let submission_log = directory.path().join("submissions.jsonl").to_string_lossy().into_owned();
+        // Nothing listens on a port that was just given back.
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut aoc_service = AocService::new(AocInputSources::default(), String::from("www/aoc_problems"));
+        aoc_service.client = AocClient::new(AocClientSettings {
+            base_url: format!("http://127.0.0.1:{}", port),
+            session: Some(String::from("test-session")),
+            min_interval: 0.0,
+            timeout: 5,
+            submission_log: submission_log.clone(),
+            ..AocClientSettings::default()
+        });
+        aoc_service.submissions = AocSubmissionLog::new(&submission_log);
+
+        assert!(aoc_service.submit_answer(2022, 11, 1, "10605").is_err());
+
+        let attempts = aoc_service.list_submissions(2022, 11, 1).unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!((attempts[0].verdict, attempts[0].wait), (AocSubmissionVerdict::Failed, None));
+        assert!(!attempts[0].message.is_empty());
+        // A failed attempt doesn't hold back sending the answer again.
+        assert_eq!(check_submission(&attempts, "10605", now_millis()), Ok(()));
+    }
+}
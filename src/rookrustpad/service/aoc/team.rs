@@ -0,0 +1,48 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+
+use lazy_regex::regex;
+
+use crate::rookrustpad::service::aoc::params::AocParamInput;
+use crate::rookrustpad::service::aoc::status::PARTS_PER_DAY;
+use crate::rookrustpad::service::aoc::{AocAnswerRequest, AocProblem, AocService};
+
+/// User names are directories next to the years, so they are plain names that can't be a year.
+pub fn is_user_name(name: &str) -> bool {
+    regex!(r"^[A-Za-z0-9_-]{1,64}$").is_match(name) && !regex!(r"^\d{4}$").is_match(name)
+}
+
+impl AocService {
+    /// Everyone with inputs of their own.
+    pub fn list_users(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let users: BTreeSet<String> = self.list_all_inputs()?.into_iter().filter_map(|i| i.user).collect();
+        Ok(users.into_iter().collect())
+    }
+
+    /// Answers to run for a team's view of a day: each user's own input for every part with a solver.
+    pub fn team_requests(&self, year: u32, day: u32) -> Result<Vec<AocAnswerRequest>, Box<dyn Error>> {
+        let inputs = self.list_all_inputs()?;
+
+        let mut requests = vec![];
+        for user in self.list_users()? {
+            for part in 1..=PARTS_PER_DAY {
+                let has_input = inputs.iter().any(|i| {
+                    i.user.as_ref() == Some(&user) && i.year == year && i.day == day && i.part == part && i.dataset.is_none()
+                });
+                if has_input && self.problem_answers.contains_key(&AocProblem { year, day, part }) {
+                    requests.push(AocAnswerRequest {
+                        year,
+                        day,
+                        part,
+                        user: Some(user.clone()),
+                        dataset: None,
+                        variant: None,
+                        params: AocParamInput::new(),
+                    });
+                }
+            }
+        }
+
+        Ok(requests)
+    }
+}
@@ -0,0 +1,380 @@
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rookrustpad::service::aoc::team::is_user_name;
+use crate::rookrustpad::service::aoc::{AocAnswer, AocService, AocVerdict};
+
+pub const DEFAULT_TIMER_FILE: &str = "data/aoc_timers.json";
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AocTimerState {
+    Running,
+    Paused,
+    Stopped,
+}
+
+/// What the timer changes to when asked from a page or the command line.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AocTimerAction {
+    /// Starts a new timer or resumes a paused or stopped one.
+    Start,
+    Pause,
+    Stop,
+    /// Forgets the timer.
+    Reset,
+}
+
+/// The correct verdict a timer was stopped by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocTimerSolve {
+    /// Milliseconds since the epoch.
+    pub solved_at: u64,
+    pub answer: String,
+    /// "submission" when AoC accepted the answer, "run" when a solver matched the expected answer.
+    pub source: String,
+    /// Seconds the solver took, when the verdict came from a run.
+    pub execution_time: Option<f64>,
+}
+
+/// Time spent on one part by one person, which can be paused and resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AocTimer {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub user: Option<String>,
+    pub state: AocTimerState,
+    /// Milliseconds since the epoch when the timer was first started.
+    pub started_at: u64,
+    /// Milliseconds since the epoch when the timer last started running.
+    pub running_since: Option<u64>,
+    /// Milliseconds counted before running_since.
+    pub elapsed: u64,
+    pub solve: Option<AocTimerSolve>,
+}
+
+impl AocTimer {
+    fn new(year: u32, day: u32, part: u8, user: &Option<String>, now: u64) -> AocTimer {
+        AocTimer {
+            year,
+            day,
+            part,
+            user: user.clone(),
+            state: AocTimerState::Running,
+            started_at: now,
+            running_since: Some(now),
+            elapsed: 0,
+            solve: None,
+        }
+    }
+
+    fn is_for(&self, year: u32, day: u32, part: u8, user: &Option<String>) -> bool {
+        self.year == year && self.day == day && self.part == part && &self.user == user
+    }
+
+    /// Milliseconds spent so far, not counting pauses.
+    pub fn elapsed_at(&self, now: u64) -> u64 {
+        self.elapsed + self.running_since.map(|since| now.saturating_sub(since)).unwrap_or(0)
+    }
+
+    /// Seconds spent so far.
+    pub fn seconds(&self) -> i64 {
+        (self.elapsed_at(now_millis()) / 1000) as i64
+    }
+
+    fn halt(&mut self, state: AocTimerState, now: u64) {
+        self.elapsed = self.elapsed_at(now);
+        self.running_since = None;
+        self.state = state;
+    }
+
+    fn apply(&mut self, action: AocTimerAction, now: u64) -> Result<(), String> {
+        match (action, self.state) {
+            (AocTimerAction::Start, AocTimerState::Running) => Err(String::from("The timer is already running")),
+            (AocTimerAction::Start, _) if self.solve.is_some() => Err(String::from("The part is solved, reset the timer to time it again")),
+            (AocTimerAction::Start, _) => {
+                self.running_since = Some(now);
+                self.state = AocTimerState::Running;
+                Ok(())
+            },
+            (AocTimerAction::Pause, AocTimerState::Running) => {
+                self.halt(AocTimerState::Paused, now);
+                Ok(())
+            },
+            (AocTimerAction::Pause, _) => Err(String::from("The timer isn't running")),
+            (AocTimerAction::Stop, AocTimerState::Stopped) => Err(String::from("The timer is already stopped")),
+            (AocTimerAction::Stop, _) => {
+                self.halt(AocTimerState::Stopped, now);
+                Ok(())
+            },
+            (AocTimerAction::Reset, _) => Ok(()),
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Every timer, kept in one json file.
+#[derive(Debug)]
+pub struct AocTimers {
+    pub path: String,
+    lock: Mutex<()>,
+}
+
+impl AocTimers {
+    pub fn new(path: &str) -> AocTimers {
+        AocTimers { path: path.to_string(), lock: Mutex::new(()) }
+    }
+
+    fn read(&self) -> Result<Vec<AocTimer>, Box<dyn Error>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents).map_err(|e| format!("Invalid timers in {}: {}", self.path, e))?),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(vec![]),
+            Err(error) => Err(format!("Unable to read {}: {}", self.path, error).into()),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<AocTimer>, Box<dyn Error>> {
+        let _lock = self.lock.lock().map_err(|_| "The timers are unavailable")?;
+        self.read()
+    }
+
+    /// Changes the timers under the lock, they are only written when f succeeds.
+    fn update<T, F>(&self, f: F) -> Result<T, Box<dyn Error>>
+        where F: FnOnce(&mut Vec<AocTimer>) -> Result<T, Box<dyn Error>>
+    {
+        let _lock = self.lock.lock().map_err(|_| "The timers are unavailable")?;
+        let mut timers = self.read()?;
+        let result = f(&mut timers)?;
+
+        if let Some(directory) = Path::new(&self.path).parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&timers)?)?;
+        Ok(result)
+    }
+}
+
+impl AocService {
+    pub fn get_timer(&self, year: u32, day: u32, part: u8, user: &Option<String>) -> Result<Option<AocTimer>, Box<dyn Error>> {
+        Ok(self.timers.load()?.into_iter().find(|t| t.is_for(year, day, part, user)))
+    }
+
+    /// Everyone's timers, or one person's, by year, day and part.
+    pub fn list_timers(&self, user: &Option<String>) -> Result<Vec<AocTimer>, Box<dyn Error>> {
+        let mut timers: Vec<AocTimer> = self.timers.load()?
+            .into_iter()
+            .filter(|t| user.is_none() || &t.user == user)
+            .collect();
+        timers.sort_by(|a, b| (a.year, a.day, a.part, &a.user).cmp(&(b.year, b.day, b.part, &b.user)));
+        Ok(timers)
+    }
+
+    /// Starts, pauses, stops or resets a part's timer, returning it as it is afterwards.
+    pub fn update_timer(&self, year: u32, day: u32, part: u8, user: &Option<String>, action: AocTimerAction) -> Result<Option<AocTimer>, Box<dyn Error>> {
+        if let Some(user) = user.as_ref().filter(|user| !is_user_name(user)) {
+            return Err(format!("Invalid user name: {}", user).into());
+        }
+
+        let now = now_millis();
+        self.timers.update(|timers| {
+            let position = timers.iter().position(|t| t.is_for(year, day, part, user));
+            match (action, position) {
+                (AocTimerAction::Reset, Some(position)) => {
+                    timers.remove(position);
+                    Ok(None)
+                },
+                (AocTimerAction::Start, None) => {
+                    let timer = AocTimer::new(year, day, part, user, now);
+                    timers.push(timer.clone());
+                    Ok(Some(timer))
+                },
+                (_, None) => Err("There is no timer for the part".into()),
+                (action, Some(position)) => {
+                    timers[position].apply(action, now)?;
+                    Ok(Some(timers[position].clone()))
+                },
+            }
+        })
+    }
+
+    /// Stops the part's timer at its first correct verdict and keeps how it was solved.
+    /// Parts nobody timed are left alone.
+    fn attach_solve(&self, year: u32, day: u32, part: u8, user: &Option<String>, solve: AocTimerSolve) -> Result<(), Box<dyn Error>> {
+        let unsolved = self.timers.load()?.iter().any(|t| t.is_for(year, day, part, user) && t.solve.is_none());
+        if !unsolved {
+            return Ok(());
+        }
+
+        self.timers.update(|timers| {
+            if let Some(timer) = timers.iter_mut().find(|t| t.is_for(year, day, part, user) && t.solve.is_none()) {
+                if timer.state != AocTimerState::Stopped {
+                    timer.halt(AocTimerState::Stopped, solve.solved_at);
+                }
+                println!("Solved {}/{}/{} after {}s", year, day, part, timer.elapsed / 1000);
+                timer.solve = Some(solve);
+            }
+            Ok(())
+        })
+    }
+
+    /// Submissions are sent with the shared session, so they stop the shared timer.
+    pub fn attach_submission(&self, year: u32, day: u32, part: u8, answer: &str) -> Result<(), Box<dyn Error>> {
+        let execution_time = self.history.latest(year, day, part, &None, &None).map(|run| run.answer.execution_time);
+        self.attach_solve(year, day, part, &None, AocTimerSolve {
+            solved_at: now_millis(),
+            answer: answer.to_string(),
+            source: String::from("submission"),
+            execution_time,
+        })
+    }
+
+    /// A run on someone's main input that matches the expected answer.
+    pub fn attach_run(&self, answer: &AocAnswer) -> Result<(), Box<dyn Error>> {
+        let Ok(result) = &answer.result else {
+            return Ok(());
+        };
+        if answer.dataset.is_some() || answer.verdict() != AocVerdict::Correct {
+            return Ok(());
+        }
+
+        self.attach_solve(answer.year, answer.day, answer.part, &answer.user, AocTimerSolve {
+            solved_at: now_millis(),
+            answer: result.clone(),
+            source: String::from("run"),
+            execution_time: Some(answer.execution_time),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rookrustpad::service::aoc::history::AocRunHistory;
+    use crate::rookrustpad::service::aoc::input_source::AocInputSources;
+    use crate::rookrustpad::service::aoc::params::AocParams;
+
+    fn service(directory: &tempfile::TempDir) -> AocService {
+        let path = |name: &str| directory.path().join(name).to_string_lossy().into_owned();
+        let mut aoc_service = AocService::new(AocInputSources::default(), String::from("www/aoc_problems"));
+        aoc_service.timers = AocTimers::new(&path("timers.json"));
+        aoc_service.history = AocRunHistory::new(&path("history"));
+        aoc_service
+    }
+
+    fn answer(part: u8, dataset: Option<&str>, result: &str, expected: &str) -> AocAnswer {
+        AocAnswer {
+            year: 2022,
+            day: 11,
+            part,
+            user: None,
+            dataset: dataset.map(String::from),
+            variant: Some(String::from("default")),
+            params: AocParams::default(),
+            result: Ok(result.to_string()),
+            artifacts: vec![],
+            expected: Some(expected.to_string()),
+            default_params: true,
+            log: String::new(),
+            execution_time: 0.25,
+        }
+    }
+
+    fn solved_with(aoc_service: &AocService, part: u8) -> Option<(String, String)> {
+        let timer = aoc_service.get_timer(2022, 11, part, &None).unwrap()?;
+        timer.solve.map(|solve| (solve.answer, solve.source))
+    }
+
+    #[test]
+    fn counts_time_only_while_running() {
+        let mut timer = AocTimer::new(2022, 11, 1, &None, 1_000);
+        timer.apply(AocTimerAction::Pause, 3_000).unwrap();
+        assert_eq!(timer.elapsed_at(9_000), 2_000);
+        assert_eq!(timer.apply(AocTimerAction::Pause, 4_000), Err(String::from("The timer isn't running")));
+
+        timer.apply(AocTimerAction::Start, 10_000).unwrap();
+        assert_eq!(timer.apply(AocTimerAction::Start, 10_500), Err(String::from("The timer is already running")));
+        assert_eq!(timer.elapsed_at(11_000), 3_000);
+
+        timer.apply(AocTimerAction::Stop, 11_500).unwrap();
+        assert_eq!((timer.state, timer.running_since), (AocTimerState::Stopped, None));
+        assert_eq!(timer.elapsed_at(20_000), 3_500);
+        assert_eq!(timer.apply(AocTimerAction::Stop, 12_000), Err(String::from("The timer is already stopped")));
+        assert_eq!(timer.started_at, 1_000);
+    }
+
+    #[test]
+    fn solved_timers_only_start_again_after_a_reset() {
+        let mut timer = AocTimer::new(2022, 11, 1, &None, 1_000);
+        timer.halt(AocTimerState::Stopped, 2_000);
+        timer.solve = Some(AocTimerSolve { solved_at: 2_000, answer: String::from("10"), source: String::from("run"), execution_time: None });
+
+        assert_eq!(timer.apply(AocTimerAction::Start, 3_000), Err(String::from("The part is solved, reset the timer to time it again")));
+        assert_eq!(timer.state, AocTimerState::Stopped);
+        assert_eq!(timer.apply(AocTimerAction::Reset, 3_000), Ok(()));
+    }
+
+    #[test]
+    fn updates_and_resets_saved_timers() {
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(&directory);
+        let alice = Some(String::from("alice"));
+
+        let error = aoc_service.update_timer(2022, 11, 1, &None, AocTimerAction::Pause).unwrap_err();
+        assert_eq!(error.to_string(), "There is no timer for the part");
+        let started = aoc_service.update_timer(2022, 11, 1, &alice, AocTimerAction::Start).unwrap().unwrap();
+        assert_eq!((started.state, started.user.clone()), (AocTimerState::Running, alice.clone()));
+        let paused = aoc_service.update_timer(2022, 11, 1, &alice, AocTimerAction::Pause).unwrap().unwrap();
+        assert_eq!(paused.state, AocTimerState::Paused);
+        assert!(aoc_service.update_timer(2022, 11, 1, &alice, AocTimerAction::Pause).is_err());
+        assert!(aoc_service.update_timer(2022, 11, 1, &Some(String::from("../x")), AocTimerAction::Start).is_err());
+
+        assert_eq!(aoc_service.get_timer(2022, 11, 1, &alice).unwrap().map(|t| t.state), Some(AocTimerState::Paused));
+        assert!(aoc_service.get_timer(2022, 11, 1, &None).unwrap().is_none());
+
+        assert!(aoc_service.update_timer(2022, 11, 1, &alice, AocTimerAction::Reset).unwrap().is_none());
+        assert!(aoc_service.list_timers(&None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_the_first_correct_verdict_of_timed_parts() {
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(&directory);
+        aoc_service.update_timer(2022, 11, 1, &None, AocTimerAction::Start).unwrap();
+
+        aoc_service.attach_submission(2022, 11, 1, "10605").unwrap();
+        aoc_service.attach_submission(2022, 11, 1, "10606").unwrap();
+        aoc_service.attach_submission(2022, 11, 2, "2713310158").unwrap();
+
+        assert_eq!(solved_with(&aoc_service, 1), Some((String::from("10605"), String::from("submission"))));
+        let timer = aoc_service.get_timer(2022, 11, 1, &None).unwrap().unwrap();
+        assert_eq!((timer.state, timer.running_since), (AocTimerState::Stopped, None));
+        // Nobody timed part 2.
+        assert_eq!(aoc_service.list_timers(&None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn only_correct_runs_on_the_main_input_stop_timers() {
+        let directory = tempfile::tempdir().unwrap();
+        let aoc_service = service(&directory);
+        aoc_service.update_timer(2022, 11, 1, &None, AocTimerAction::Start).unwrap();
+
+        aoc_service.attach_run(&answer(1, Some("test"), "10605", "10605")).unwrap();
+        aoc_service.attach_run(&answer(1, None, "10604", "10605")).unwrap();
+        assert_eq!(solved_with(&aoc_service, 1), None);
+
+        aoc_service.attach_run(&answer(1, None, "10605", "10605")).unwrap();
+        assert_eq!(solved_with(&aoc_service, 1), Some((String::from("10605"), String::from("run"))));
+        let timer = aoc_service.get_timer(2022, 11, 1, &None).unwrap().unwrap();
+        assert_eq!(timer.solve.and_then(|solve| solve.execution_time), Some(0.25));
+    }
+}
@@ -3,6 +3,7 @@ use crate::rookrustpad::service::aoc::AocService;
 pub mod day11;
 
 pub fn configure_service(aoc_service: &mut AocService) {
-    aoc_service.register_answer(2022, 11, 1, day11::part1);
-    aoc_service.register_answer(2022, 11, 2, day11::part2);
+    aoc_service.register_answer(2022, 11, 1, aoc_solver!(y2022, day11, part1).with_params(day11::PART1_PARAMS));
+    aoc_service.register_answer(2022, 11, 2, aoc_solver!(y2022, day11, part2, "naive").with_params(day11::PART2_PARAMS));
+    aoc_service.register_answer(2022, 11, 2, aoc_solver!(y2022, day11, part2_vec_soa, "vec_soa").with_params(day11::PART2_PARAMS));
 }
@@ -1,4 +1,6 @@
 use crate::rookrustpad::service::aoc::AocFunctionResult;
+use crate::rookrustpad::service::aoc::output::{AocArtifact, AocOutput};
+use crate::rookrustpad::service::aoc::params::{AocParam, AocParams};
 
 use std::collections::HashMap;
 use std::fs::File;
@@ -17,16 +19,17 @@ pub enum Expression {
 }
 
 impl Expression {
-    pub fn eval(&self, initial: u64) -> u64 {
+    /// None when the worry level no longer fits in a u64.
+    pub fn eval(&self, initial: u64) -> Option<u64> {
         match self {
             Expression::Old =>
-                initial,
+                Some(initial),
             Expression::Number(n) =>
-                *n,
+                Some(*n),
             Expression::Multiply(left, right) =>
-                left.eval(initial) * right.eval(initial),
+                left.eval(initial)?.checked_mul(right.eval(initial)?),
             Expression::Add(left, right) =>
-                left.eval(initial) + right.eval(initial)
+                left.eval(initial)?.checked_add(right.eval(initial)?)
         }
     }
 }
@@ -44,16 +47,17 @@ struct MonkeyInfo {
 
 impl MonkeyInfo {
 
-    pub fn inspect_items(&mut self) {
+    pub fn inspect_items(&mut self) -> Result<(), Box<dyn Error>> {
         // Change worry level using expression.
         for item in &mut self.items {
-            *item = self.expression.eval(*item);
+            *item = self.expression.eval(*item).ok_or(format!("Worry level overflowed at monkey {}", self.id))?;
         }
-        self.inspection_count = self.inspection_count + u32::try_from(self.items.len()).unwrap();
+        self.inspection_count += u32::try_from(self.items.len()).unwrap();
+        Ok(())
     }
 
     pub fn get_new_monkey_id(&self, item: u64) -> u32 {
-        if item % self.test == 0 {
+        if item.is_multiple_of(self.test) {
             self.on_true
         }
         else {
@@ -62,7 +66,7 @@ impl MonkeyInfo {
     }
 
     pub fn test_and_get_throws(&mut self) -> Vec<(u32, u64)> {
-        let items = core::mem::replace(&mut self.items, Vec::new());
+        let items = std::mem::take(&mut self.items);
         let mut moves: Vec<(u32, u64)> = Vec::new();
 
         for item in items {
@@ -111,7 +115,7 @@ impl MonkeyInfoParser {
             return Ok(None);
         }
 
-        let id = MonkeyInfoParser::read_monkey_line(&mut line_buf)?;
+        let id = MonkeyInfoParser::read_monkey_line(&line_buf)?;
 
         MonkeyInfoParser::read_next_line(reader, &mut line_buf, "starting items")?;
         let items = MonkeyInfoParser::read_starting_items(&line_buf)?;
@@ -129,7 +133,7 @@ impl MonkeyInfoParser {
         let h2 = MonkeyInfoParser::read_test_handler(&line_buf)?;
 
         if h1.0 == h2.0 {
-            return Err(format!("Both test handlers have the same condition.").into());
+            return Err("Both test handlers have the same condition.".into());
         }
 
         let (on_true, on_false) =
@@ -162,7 +166,7 @@ impl MonkeyInfoParser {
             if size_read == 0 {
                 return Ok(false);
             }
-            if !ws_re.is_match(&line_buf) {
+            if !ws_re.is_match(line_buf) {
                 return Ok(true)
             }
         }
@@ -170,14 +174,14 @@ impl MonkeyInfoParser {
 
     fn read_monkey_line(line: &String) -> Result<u32, Box<dyn Error>> {
         let monkey_re = regex!(r"^\s*Monkey\s+(\d+):");
-        let cap = monkey_re.captures(&line).ok_or(format!("Invalid initial line: {}", line))?;
+        let cap = monkey_re.captures(line).ok_or(format!("Invalid initial line: {}", line))?;
         Ok(cap.get(1).unwrap().as_str().parse::<u32>()?)
     }
 
     fn read_starting_items(line: &String) -> Result<Vec<u64>, Box<dyn Error>>
     {
         let starting_items_re = regex!(r"^\s*Starting items:\s+([\d,\s]+)\s*");
-        let cap = starting_items_re.captures(&line).ok_or(format!("Invalid starting items: {}", line))?;
+        let cap = starting_items_re.captures(line).ok_or(format!("Invalid starting items: {}", line))?;
 
         let mut items: Vec<u64> = Vec::new();
 
@@ -191,7 +195,7 @@ impl MonkeyInfoParser {
 
     fn read_operation(line: &String) -> Result<Expression, Box<dyn Error>> {
         let operation_re = regex!(r"^\s*Operation: new = (old|\d+) ([\+\*]) (old|\d+)");
-        let cap = operation_re.captures(&line).ok_or(format!("Invalid operation: {}", line))?;
+        let cap = operation_re.captures(line).ok_or(format!("Invalid operation: {}", line))?;
 
         let left_op_str = cap.get(1).unwrap().as_str();
         let left_op = 
@@ -271,20 +275,12 @@ impl Monkees {
         self.monkees.values().fold(1, |a, b| { a * b.test })
     }
 
-    pub fn run_rounds<F>(&mut self, rounds: u32, manage_worry: F)
-        where F: Fn(&mut MonkeyInfo) -> ()
-    {
-        for _ in 1..=rounds {
-            self.run_round(&manage_worry);
-        }
-    }
-
-    pub fn run_round<F>(&mut self, manage_worry: F)
-        where F: Fn(&mut MonkeyInfo) -> ()
+    pub fn run_round<F>(&mut self, manage_worry: F) -> Result<(), Box<dyn Error>>
+        where F: Fn(&mut MonkeyInfo)
     {
         for m_id in &self.ids {
             let monkey = self.monkees.get_mut(m_id).unwrap();
-            monkey.inspect_items();
+            monkey.inspect_items()?;
             manage_worry(monkey);
 
             let throws = monkey.test_and_get_throws();
@@ -292,14 +288,42 @@ impl Monkees {
                 self.monkees.get_mut(&new_mid).unwrap().items.push(item);
             }
         }
+        Ok(())
     }
 
     pub fn get_monkey_business(&self) -> u64 {
-        let mut counts: Vec<u32> = self.monkees.iter().map(|(_, m)| { m.inspection_count }).collect();
+        let mut counts: Vec<u32> = self.monkees.values().map(|m| { m.inspection_count }).collect();
         counts.sort();
 
-        let monkey_business = u64::from(counts[counts.len() - 1]) * u64::from(counts[counts.len() - 2]);
-        monkey_business
+        u64::from(counts[counts.len() - 1]) * u64::from(counts[counts.len() - 2])
+    }
+
+    pub fn get_inspection_counts(&self) -> Vec<(u32, u32)> {
+        self.ids.iter().map(|id| (*id, self.monkees[id].inspection_count)).collect()
+    }
+
+    /// Runs the rounds, noting the monkey business every sample_every rounds.
+    pub fn run_rounds_sampled<F>(&mut self, rounds: u32, sample_every: u32, manage_worry: F) -> Result<Vec<(f64, f64)>, Box<dyn Error>>
+        where F: Fn(&mut MonkeyInfo)
+    {
+        let mut samples = vec![];
+        for round in 1..=rounds {
+            self.run_round(&manage_worry).map_err(|e| format!("Round {}: {}", round, e))?;
+            if round % sample_every == 0 || round == rounds {
+                samples.push((round as f64, self.get_monkey_business() as f64));
+            }
+        }
+        Ok(samples)
+    }
+
+    pub fn get_output(&self, samples: Vec<(f64, f64)>) -> AocOutput {
+        let counts = self.get_inspection_counts()
+            .into_iter()
+            .map(|(id, count)| (format!("Monkey {}", id), count));
+
+        AocOutput::new(self.get_monkey_business())
+            .with(AocArtifact::table("Inspections", counts))
+            .with(AocArtifact::series("Monkey business by round", samples))
     }
 
     pub fn load(input_path: String) -> Result<Monkees, Box<dyn Error>> {
@@ -308,30 +332,91 @@ impl Monkees {
     }
 }
 
-pub fn part1(input_path: String, _log: &mut String) -> AocFunctionResult {
+pub const PART1_PARAMS: &[AocParam] = &[
+    AocParam::integer("rounds", "Rounds to play", 20, 1, 100_000),
+    AocParam::integer("divisor", "Worry levels are divided by this after each inspection", 3, 1, 1_000_000),
+];
+
+pub const PART2_PARAMS: &[AocParam] = &[
+    AocParam::integer("rounds", "Rounds to play", 10_000, 1, 100_000),
+];
+
+/// Samples about a hundred points whatever the number of rounds.
+fn sample_interval(rounds: u32) -> u32 {
+    (rounds / 100).max(1)
+}
+
+pub fn part1(input_path: String, params: &AocParams, _log: &mut String) -> AocFunctionResult {
+    let rounds = params.integer("rounds")? as u32;
+    let divisor = params.integer("divisor")? as u64;
     let mut monkees = Monkees::load(input_path)?;
 
-    monkees.run_rounds(20, |m| {
+    let samples = monkees.run_rounds_sampled(rounds, sample_interval(rounds), |m| {
         for i in 0..m.items.len() {
-            m.items[i] = m.items[i] / 3;
+            m.items[i] /= divisor;
         }
-    });
+    })?;
 
-    let monkey_business = monkees.get_monkey_business();
-    Ok(format!("{}", monkey_business))
+    Ok(monkees.get_output(samples))
 }
 
 
-pub fn part2(input_path: String, _log: &mut String) -> AocFunctionResult {
+pub fn part2(input_path: String, params: &AocParams, _log: &mut String) -> AocFunctionResult {
+    let rounds = params.integer("rounds")? as u32;
     let mut monkees = Monkees::load(input_path)?;
     let modulus = monkees.get_modulus();
 
-    monkees.run_rounds(10000, |m| {
+    let samples = monkees.run_rounds_sampled(rounds, sample_interval(rounds), |m| {
         for i in 0..m.items.len() {
-            m.items[i] = m.items[i] % modulus;
+            m.items[i] %= modulus;
         }
-    });
+    })?;
+
+    Ok(monkees.get_output(samples))
+}
+/// Part 2 with the monkeys split into parallel vectors and every item
+/// followed on its own. Items never affect each other, so each one can run
+/// all its rounds before the next starts. An item thrown to a later monkey
+/// is inspected again in the same round, one thrown to an earlier monkey
+/// waits for the next round.
+pub fn part2_vec_soa(input_path: String, params: &AocParams, _log: &mut String) -> AocFunctionResult {
+    let rounds = params.integer("rounds")?;
+    let mut monkey_infos = MonkeyInfoParser::read_all_from_file(&input_path)?;
+    monkey_infos.sort_by_key(|m| m.id);
+
+    let index_of = |id: u32| monkey_infos.iter().position(|m| m.id == id).ok_or(format!("Unknown monkey: {}", id));
+
+    let ids: Vec<u32> = monkey_infos.iter().map(|m| m.id).collect();
+    let tests: Vec<u64> = monkey_infos.iter().map(|m| m.test).collect();
+    let on_true: Vec<usize> = monkey_infos.iter().map(|m| index_of(m.on_true)).collect::<Result<_, _>>()?;
+    let on_false: Vec<usize> = monkey_infos.iter().map(|m| index_of(m.on_false)).collect::<Result<_, _>>()?;
+    let modulus: u64 = tests.iter().product();
+
+    let mut worries: Vec<u64> = vec![];
+    let mut holders: Vec<usize> = vec![];
+    for (i, m) in monkey_infos.iter().enumerate() {
+        worries.extend(&m.items);
+        holders.extend(std::iter::repeat_n(i, m.items.len()));
+    }
 
-    let monkey_business = monkees.get_monkey_business();
-    Ok(format!("{}", monkey_business))
-}
\ No newline at end of file
+    let mut counts: Vec<u64> = vec![0; monkey_infos.len()];
+    for (worry, holder) in worries.iter_mut().zip(holders.iter_mut()) {
+        let mut round = 0;
+        while round < rounds {
+            let m = *holder;
+            counts[m] += 1;
+            *worry = monkey_infos[m].expression.eval(*worry).ok_or("Worry level overflowed")? % modulus;
+            *holder = if worry.is_multiple_of(tests[m]) { on_true[m] } else { on_false[m] };
+            if *holder < m {
+                round += 1;
+            }
+        }
+    }
+
+    let mut sorted = counts.clone();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let monkey_business = sorted[0] * sorted[1];
+
+    let inspections = ids.iter().zip(&counts).map(|(id, count)| (format!("Monkey {}", id), count));
+    Ok(AocOutput::new(monkey_business).with(AocArtifact::table("Inspections", inspections)))
+}
@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use futures::channel::oneshot;
+use serde::Serialize;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverPoolError {
+    QueueFull { retry_after: u64 },
+    Canceled,
+}
+
+impl fmt::Display for SolverPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverPoolError::QueueFull { retry_after } =>
+                write!(f, "Solver queue is full, retry in {} seconds.", retry_after),
+            SolverPoolError::Canceled =>
+                write!(f, "Solver job was canceled before it finished."),
+        }
+    }
+}
+
+impl Error for SolverPoolError {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SolverPoolStats {
+    pub workers: usize,
+    pub max_queue: usize,
+    pub active: usize,
+    pub queued: usize,
+    pub completed: usize,
+    pub rejected: usize,
+    pub utilization: f64,
+}
+
+#[derive(Debug, Default)]
+struct SolverPoolCounters {
+    /// Jobs accepted and not finished yet, running or waiting.
+    pending: AtomicUsize,
+    active: AtomicUsize,
+    queued: AtomicUsize,
+    completed: AtomicUsize,
+    rejected: AtomicUsize,
+}
+
+/// A fixed set of threads for running CPU heavy solvers away from the
+/// http workers. Once every thread is busy, up to max_queue jobs wait
+/// for one and further jobs are rejected.
+pub struct SolverPool {
+    workers: usize,
+    max_queue: usize,
+    retry_after: u64,
+    sender: Mutex<Sender<Job>>,
+    counters: Arc<SolverPoolCounters>,
+}
+
+impl SolverPool {
+    pub fn new(workers: usize, max_queue: usize, retry_after: u64) -> SolverPool {
+        let workers = workers.max(1);
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let counters = Arc::new(SolverPoolCounters::default());
+
+        for n in 0..workers {
+            let receiver = receiver.clone();
+            let counters = counters.clone();
+            thread::Builder::new()
+                .name(format!("solver-{}", n))
+                .spawn(move || SolverPool::work(receiver, counters))
+                .expect("Unable to start solver thread");
+        }
+
+        SolverPool {
+            workers,
+            max_queue,
+            retry_after,
+            sender: Mutex::new(sender),
+            counters,
+        }
+    }
+
+    fn work(receiver: Arc<Mutex<Receiver<Job>>>, counters: Arc<SolverPoolCounters>) {
+        loop {
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+
+            // The sender only goes away with the pool.
+            let Ok(job) = job else {
+                return;
+            };
+
+            counters.queued.fetch_sub(1, Ordering::SeqCst);
+            counters.active.fetch_add(1, Ordering::SeqCst);
+            job();
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Queues a job, returning a future for its result or QueueFull if every
+    /// thread is busy and there are already max_queue jobs waiting.
+    pub fn submit<F, R>(&self, job: F) -> Result<impl Future<Output = Result<R, SolverPoolError>>, SolverPoolError>
+        where
+            F: FnOnce() -> R + Send + 'static,
+            R: Send + 'static
+    {
+        let capacity = self.workers + self.max_queue;
+        let reserved = self.counters.pending.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |pending| {
+            if pending < capacity { Some(pending + 1) } else { None }
+        });
+
+        if reserved.is_err() {
+            self.counters.rejected.fetch_add(1, Ordering::SeqCst);
+            return Err(SolverPoolError::QueueFull { retry_after: self.retry_after });
+        }
+        self.counters.queued.fetch_add(1, Ordering::SeqCst);
+
+        let (result_sender, result_receiver) = oneshot::channel::<R>();
+        let counters = self.counters.clone();
+        let job: Job = Box::new(move || {
+            let result = catch_unwind(AssertUnwindSafe(job));
+
+            // Counted before the waiter hears back, so it can submit again right away.
+            counters.active.fetch_sub(1, Ordering::SeqCst);
+            counters.completed.fetch_add(1, Ordering::SeqCst);
+            counters.pending.fetch_sub(1, Ordering::SeqCst);
+
+            match result {
+                // The waiter may have gone away, nothing to do about it.
+                Ok(result) => {
+                    let _ = result_sender.send(result);
+                },
+                // A panicking solver drops its result channel, the waiter sees Canceled.
+                Err(_) => println!("Solver job panicked on {:?}", thread::current().name()),
+            }
+        });
+
+        let sent = self.sender
+            .lock()
+            .map_err(|_| SolverPoolError::Canceled)
+            .and_then(|sender| sender.send(job).map_err(|_| SolverPoolError::Canceled));
+
+        if let Err(error) = sent {
+            self.counters.queued.fetch_sub(1, Ordering::SeqCst);
+            self.counters.pending.fetch_sub(1, Ordering::SeqCst);
+            return Err(error);
+        }
+
+        Ok(async move {
+            result_receiver.await.map_err(|_| SolverPoolError::Canceled)
+        })
+    }
+
+    /// Submits a job and waits for it.
+    pub async fn run<F, R>(&self, job: F) -> Result<R, SolverPoolError>
+        where
+            F: FnOnce() -> R + Send + 'static,
+            R: Send + 'static
+    {
+        self.submit(job)?.await
+    }
+
+    pub fn stats(&self) -> SolverPoolStats {
+        let active = self.counters.active.load(Ordering::SeqCst);
+        SolverPoolStats {
+            workers: self.workers,
+            max_queue: self.max_queue,
+            active,
+            queued: self.counters.queued.load(Ordering::SeqCst),
+            completed: self.counters.completed.load(Ordering::SeqCst),
+            rejected: self.counters.rejected.load(Ordering::SeqCst),
+            utilization: active as f64 / self.workers as f64,
+        }
+    }
+}
+
+impl fmt::Debug for SolverPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SolverPool")
+            .field("workers", &self.workers)
+            .field("max_queue", &self.max_queue)
+            .field("retry_after", &self.retry_after)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn runs_jobs_on_idle_workers_without_a_queue() {
+        let pool = SolverPool::new(1, 0, 5);
+        assert_eq!(pool.run(|| 2 + 2).await, Ok(4));
+        assert_eq!(pool.run(|| 3 + 3).await, Ok(6));
+        assert_eq!(pool.stats().completed, 2);
+    }
+
+    #[actix_web::test]
+    async fn rejects_jobs_once_workers_are_busy_and_the_queue_is_full() {
+        let pool = SolverPool::new(1, 1, 7);
+        let (started_sender, started) = channel::<()>();
+        let (release, release_receiver) = channel::<()>();
+        let blocking = pool.submit(move || {
+            started_sender.send(()).unwrap();
+            release_receiver.recv().unwrap();
+            1
+        }).unwrap();
+        started.recv().unwrap();
+
+        let waiting = pool.submit(|| 2).unwrap();
+        assert_eq!(pool.submit(|| 3).err(), Some(SolverPoolError::QueueFull { retry_after: 7 }));
+
+        let stats = pool.stats();
+        assert_eq!((stats.workers, stats.max_queue), (1, 1));
+        assert_eq!((stats.active, stats.queued, stats.completed, stats.rejected), (1, 1, 0, 1));
+        assert_eq!(stats.utilization, 1.0);
+
+        release.send(()).unwrap();
+        assert_eq!(blocking.await, Ok(1));
+        assert_eq!(waiting.await, Ok(2));
+        let stats = pool.stats();
+        assert_eq!((stats.active, stats.queued, stats.completed, stats.rejected), (0, 0, 2, 1));
+        assert_eq!(stats.utilization, 0.0);
+    }
+
+    #[actix_web::test]
+    async fn a_panicking_job_is_canceled() {
+        let pool = SolverPool::new(1, 0, 5);
+        assert_eq!(pool.run(|| -> u32 { panic!("solver failed") }).await, Err(SolverPoolError::Canceled));
+
+        // The thread survives and the job no longer counts against the queue.
+        assert_eq!(pool.run(|| 5).await, Ok(5));
+        assert_eq!(pool.stats().completed, 2);
+    }
+}